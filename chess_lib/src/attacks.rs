@@ -0,0 +1,217 @@
+//! Which pieces attack a square, which pieces give check and which pieces are pinned to their king.
+//!
+//! A piece attacks a square if it could capture a piece standing there, whether a piece is there or not.
+//! Attacks do not care whose turn it is or whether the move would leave the king in check, so a pinned
+//! piece still attacks the squares it could otherwise move to.
+
+use crate::bitboard::{self, Bitboard};
+use crate::{Board, Color, Game, PieceType, Square};
+
+
+impl Board {
+
+    /// Returns `true` if any piece of the color `by` attacks the square.
+    pub fn is_attacked(&self, square: Square, by: Color) -> bool {
+
+        !(self.attackers(square) & self.get_color_pieces(by)).is_empty()
+    }
+
+    /// The pieces of both colors that attack the square.
+    pub fn attackers(&self, square: Square) -> Bitboard {
+
+        self.attackers_with(square, self.get_occupied())
+    }
+
+    /// The pieces that would attack the square if only the squares in `occupied` had pieces on them.
+    /// Taking pieces out of `occupied` lets the rooks, bishops and queens behind them attack through them.
+    pub fn attackers_with(&self, square: Square, occupied: Bitboard) -> Bitboard {
+
+        let queens = self.pieces[PieceType::Queen as usize];
+        let rooks = self.pieces[PieceType::Rook as usize] | queens;
+        let bishops = self.pieces[PieceType::Bishop as usize] | queens;
+        let pawns = self.pieces[PieceType::Pawn as usize];
+
+        //A pawn attacks the square if a pawn of the other color on the square would attack the pawn
+        let attackers = (bitboard::pawn_attacks(square, Color::Black) & pawns & self.get_color_pieces(Color::White))
+            | (bitboard::pawn_attacks(square, Color::White) & pawns & self.get_color_pieces(Color::Black))
+            | (bitboard::knight_attacks(square) & self.pieces[PieceType::Knight as usize])
+            | (bitboard::king_attacks(square) & self.pieces[PieceType::King as usize])
+            | (bitboard::rook_attacks(square, occupied) & rooks)
+            | (bitboard::bishop_attacks(square, occupied) & bishops);
+
+        attackers & occupied //The pieces taken out of occupied do not attack anything
+    }
+
+    /// The rooks, bishops and queens that attack the square through exactly one piece, of either color.
+    /// These would attack the square if the first piece on their line towards it moved away.
+    pub fn xray_attackers(&self, square: Square) -> Bitboard {
+
+        let occupied = self.get_occupied();
+
+        let first_blockers = (bitboard::rook_attacks(square, occupied) | bitboard::bishop_attacks(square, occupied)) & occupied;
+
+        self.attackers_with(square, occupied & !first_blockers) & !self.attackers(square)
+    }
+
+    /// The pieces of the opponent of `color` that attack the king of `color`. There are two of them in a double check.
+    pub fn checkers(&self, color: Color) -> Bitboard {
+
+        let opponent = color.opponent();
+
+        match self.king_square(color) {
+            Some(king) => self.attackers(king) & self.get_color_pieces(opponent),
+            None => Bitboard::EMPTY,
+        }
+    }
+
+    /// The pieces of `color` that can not leave the line between their king and a rook, bishop or queen of
+    /// the opponent without putting their own king in check.
+    pub fn pinned_pieces(&self, color: Color) -> Bitboard {
+
+        let Some(king) = self.king_square(color) else { return Bitboard::EMPTY };
+
+        let opponent = color.opponent();
+
+        let enemies = self.get_color_pieces(opponent);
+        let queens = self.get_pieces(opponent, PieceType::Queen);
+
+        //The sliders that would attack the king if none of its own pieces were in the way
+        let snipers = (bitboard::rook_attacks(king, enemies) & (self.get_pieces(opponent, PieceType::Rook) | queens))
+            | (bitboard::bishop_attacks(king, enemies) & (self.get_pieces(opponent, PieceType::Bishop) | queens));
+
+        let mut pinned = Bitboard::EMPTY;
+
+        for sniper in snipers {
+
+            let blockers = bitboard::between(king, sniper) & self.get_occupied();
+
+            if blockers.count() == 1 { //With two pieces in the way either of them can move away
+                pinned |= blockers & self.get_color_pieces(color);
+            }
+        }
+
+        pinned
+    }
+
+    pub(crate) fn is_in_check(&self, color: Color) -> bool {
+
+        !self.checkers(color).is_empty()
+    }
+}
+
+
+impl Game {
+
+    /// The pieces of both colors that attack the square, see [`Board::attackers`].
+    pub fn attackers(&self, square: Square) -> Bitboard {
+
+        self.board.attackers(square)
+    }
+
+    /// The rooks, bishops and queens of both colors that attack the square through one piece, see [`Board::xray_attackers`].
+    pub fn xray_attackers(&self, square: Square) -> Bitboard {
+
+        self.board.xray_attackers(square)
+    }
+
+    /// The pieces giving check to the player whose turn it is.
+    pub fn checkers(&self) -> Bitboard {
+
+        self.board.checkers(self.player_color())
+    }
+
+    /// The pieces of `color` that are pinned to their king, see [`Board::pinned_pieces`].
+    pub fn pinned_pieces(&self, color: Color) -> Bitboard {
+
+        self.board.pinned_pieces(color)
+    }
+}
+
+
+
+
+// --------------------------
+// ######### TESTS ##########
+// --------------------------
+
+#[cfg(test)]
+mod tests {
+    use crate::{Bitboard, Color, Game, Square};
+
+    fn squares(bitboard: Bitboard) -> Vec<Square> {
+        bitboard.into_iter().collect()
+    }
+
+    #[test]
+    fn attackers_of_both_colors() {
+
+        let game = Game::from_fen("7k/8/3p1n2/8/4Q3/3P1P2/8/4K2B w - - 0 1").unwrap();
+        let board = game.get_board();
+
+        assert_eq!(squares(board.attackers(Square::E5)), [Square::E4, Square::D6]);
+        assert_eq!(squares(board.attackers(Square::E4)), [Square::D3, Square::F3, Square::F6]);
+        assert_eq!(squares(board.attackers(Square::G2)), [Square::H1]); // the f3 pawn blocks the queen
+
+        assert!(board.is_attacked(Square::E4, Color::Black));
+        assert!(board.is_attacked(Square::E4, Color::White));
+        assert!(!board.is_attacked(Square::A1, Color::Black));
+        assert!(game.is_attacked(Square::H7, Color::White));
+        assert_eq!(game.attackers(Square::E5), board.attackers(Square::E5));
+    }
+
+    #[test]
+    fn attackers_behind_removed_pieces() {
+
+        let game = Game::from_fen("4k3/8/8/8/8/8/4R3/4R1K1 b - - 0 1").unwrap();
+        let board = game.get_board();
+
+        assert_eq!(squares(board.attackers(Square::E8)), [Square::E2]);
+        assert_eq!(squares(board.xray_attackers(Square::E8)), [Square::E1]);
+        assert_eq!(squares(board.attackers_with(Square::E8, board.get_occupied() & !Bitboard::from(Square::E2))), [Square::E1]);
+
+        assert_eq!(board.xray_attackers(Square::A8), Bitboard::EMPTY);
+
+        // the same from the game, with a bishop behind a pawn of the other color
+        assert_eq!(squares(game.xray_attackers(Square::E8)), [Square::E1]);
+
+        let diagonal = Game::from_fen("4k3/8/8/8/3p4/8/1B6/4K3 w - - 0 1").unwrap();
+
+        assert_eq!(squares(diagonal.xray_attackers(Square::E5)), [Square::B2]);
+        assert_eq!(diagonal.xray_attackers(Square::D4), Bitboard::EMPTY);
+    }
+
+    #[test]
+    fn checkers_in_single_and_double_check() {
+
+        assert!(Game::new().checkers().is_empty());
+
+        let single = Game::from_fen("4k3/8/8/8/8/8/8/R3K2q w - - 0 1").unwrap();
+
+        assert_eq!(squares(single.checkers()), [Square::H1]);
+        assert_eq!(single.get_board().checkers(Color::Black), Bitboard::EMPTY);
+
+        let double = Game::from_fen("4k3/8/3N4/8/8/8/8/4R1K1 b - - 0 1").unwrap();
+
+        assert_eq!(squares(double.checkers()), [Square::E1, Square::D6]);
+    }
+
+    #[test]
+    fn pinned_pieces_on_lines_and_diagonals() {
+
+        let game = Game::from_fen("4r2k/8/8/b7/8/2N5/4B3/4K3 w - - 0 1").unwrap();
+
+        assert_eq!(squares(game.pinned_pieces(Color::White)), [Square::E2, Square::C3]);
+        assert_eq!(game.pinned_pieces(Color::Black), Bitboard::EMPTY);
+
+        // two pieces between the king and the rook, neither of them is pinned
+        let blocked = Game::from_fen("4r2k/8/8/4P3/8/8/4B3/4K3 w - - 0 1").unwrap();
+
+        assert_eq!(blocked.pinned_pieces(Color::White), Bitboard::EMPTY);
+
+        // a piece of the opponent in between is not pinned to our king
+        let opponents = Game::from_fen("4r2k/8/4n3/8/8/8/8/4K3 w - - 0 1").unwrap();
+
+        assert_eq!(opponents.pinned_pieces(Color::White), Bitboard::EMPTY);
+        assert_eq!(opponents.pinned_pieces(Color::Black), Bitboard::EMPTY);
+    }
+}
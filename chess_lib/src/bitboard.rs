@@ -0,0 +1,359 @@
+//! Sets of squares kept in the 64 bits of a `u64`, with a1 as the lowest bit and h8 as the highest,
+//! and the squares each piece attacks from every square of the board.
+//!
+//! The king, knight and pawn attacks do not depend on the other pieces, so they are worked out once
+//! when compiling and only looked up. The rook and bishop attacks stop at the first piece in each direction
+//! and are looked up with magic bitboards, which are checked against walking the rays square by square.
+
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+
+use crate::magic;
+use crate::{Color, Square};
+
+
+/// A set of squares, one bit for each square of the board.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Bitboard(pub u64);
+
+impl Bitboard {
+
+    pub const EMPTY: Bitboard = Bitboard(0);
+    pub const FULL: Bitboard = Bitboard(u64::MAX);
+
+    pub fn from_square(square: Square) -> Bitboard {
+
+        Bitboard(1 << square.index())
+    }
+
+    pub fn contains(self, square: Square) -> bool {
+
+        self.0 & (1 << square.index()) != 0
+    }
+
+    pub fn is_empty(self) -> bool {
+
+        self.0 == 0
+    }
+
+    /// The number of squares in the set.
+    pub fn count(self) -> u32 {
+
+        self.0.count_ones()
+    }
+
+    /// The lowest square in the set, the one closest to a1.
+    pub fn first(self) -> Option<Square> {
+
+        match self.0 {
+            0 => None,
+            bits => Square::new(bits.trailing_zeros()),
+        }
+    }
+}
+
+impl From<Square> for Bitboard {
+
+    fn from(square: Square) -> Bitboard {
+        Bitboard::from_square(square)
+    }
+}
+
+impl BitAnd for Bitboard {
+
+    type Output = Bitboard;
+
+    fn bitand(self, other: Bitboard) -> Bitboard {
+        Bitboard(self.0 & other.0)
+    }
+}
+
+impl BitOr for Bitboard {
+
+    type Output = Bitboard;
+
+    fn bitor(self, other: Bitboard) -> Bitboard {
+        Bitboard(self.0 | other.0)
+    }
+}
+
+impl BitXor for Bitboard {
+
+    type Output = Bitboard;
+
+    fn bitxor(self, other: Bitboard) -> Bitboard {
+        Bitboard(self.0 ^ other.0)
+    }
+}
+
+impl Not for Bitboard {
+
+    type Output = Bitboard;
+
+    fn not(self) -> Bitboard {
+        Bitboard(!self.0)
+    }
+}
+
+impl BitAndAssign for Bitboard {
+
+    fn bitand_assign(&mut self, other: Bitboard) {
+        self.0 &= other.0;
+    }
+}
+
+impl BitOrAssign for Bitboard {
+
+    fn bitor_assign(&mut self, other: Bitboard) {
+        self.0 |= other.0;
+    }
+}
+
+impl BitXorAssign for Bitboard {
+
+    fn bitxor_assign(&mut self, other: Bitboard) {
+        self.0 ^= other.0;
+    }
+}
+
+impl IntoIterator for Bitboard {
+
+    type Item = Square;
+    type IntoIter = BitboardSquares;
+
+    fn into_iter(self) -> BitboardSquares {
+        BitboardSquares(self.0)
+    }
+}
+
+/// The squares of a [`Bitboard`] from the lowest to the highest.
+#[derive(Clone, Debug)]
+pub struct BitboardSquares(u64);
+
+impl Iterator for BitboardSquares {
+
+    type Item = Square;
+
+    fn next(&mut self) -> Option<Square> {
+
+        let square = Bitboard(self.0).first()?;
+
+        self.0 &= self.0 - 1; //Clears the lowest bit, which is the square we just found
+
+        Some(square)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+
+        let count = self.0.count_ones() as usize;
+
+        (count, Some(count))
+    }
+}
+
+
+//The steps are the number of files to the right and ranks up the board
+const KING_STEPS: [(i32, i32); 8] = [(-1, 1), (0, 1), (1, 1), (-1, 0), (1, 0), (-1, -1), (0, -1), (1, -1)];
+const KNIGHT_STEPS: [(i32, i32); 8] = [(1, 2), (2, 1), (2, -1), (1, -2), (-1, -2), (-2, -1), (-2, 1), (-1, 2)];
+const WHITE_PAWN_STEPS: [(i32, i32); 2] = [(-1, 1), (1, 1)];
+const BLACK_PAWN_STEPS: [(i32, i32); 2] = [(-1, -1), (1, -1)];
+
+const ROOK_DIRECTIONS: [(i32, i32); 4] = [(0, 1), (1, 0), (0, -1), (-1, 0)];
+const BISHOP_DIRECTIONS: [(i32, i32); 4] = [(1, 1), (1, -1), (-1, -1), (-1, 1)];
+
+static KING_ATTACKS: [Bitboard; 64] = step_attacks(&KING_STEPS);
+static KNIGHT_ATTACKS: [Bitboard; 64] = step_attacks(&KNIGHT_STEPS);
+static PAWN_ATTACKS: [[Bitboard; 64]; 2] = [step_attacks(&WHITE_PAWN_STEPS), step_attacks(&BLACK_PAWN_STEPS)];
+
+const fn step_attacks(steps: &[(i32, i32)]) -> [Bitboard; 64] { //The squares reached with one of the steps from every square, leaving out the steps that go off the board
+
+    let mut table = [Bitboard::EMPTY; 64];
+    let mut square = 0;
+
+    while square < 64 {
+
+        let mut bits = 0;
+        let mut step = 0;
+
+        while step < steps.len() {
+
+            let file = square as i32 % 8 + steps[step].0;
+            let rank = square as i32 / 8 + steps[step].1;
+
+            if 0 <= file && file < 8 && 0 <= rank && rank < 8 {
+                bits |= 1 << (rank * 8 + file);
+            }
+
+            step += 1;
+        }
+
+        table[square] = Bitboard(bits);
+        square += 1;
+    }
+
+    table
+}
+
+/// The squares a king on `square` attacks.
+pub fn king_attacks(square: Square) -> Bitboard {
+
+    KING_ATTACKS[square.index()]
+}
+
+/// The squares a knight on `square` attacks.
+pub fn knight_attacks(square: Square) -> Bitboard {
+
+    KNIGHT_ATTACKS[square.index()]
+}
+
+/// The two squares diagonally in front of a pawn of the given color, whether there is a piece there or not.
+pub fn pawn_attacks(square: Square, color: Color) -> Bitboard {
+
+    PAWN_ATTACKS[color as usize][square.index()]
+}
+
+/// The squares a rook on `square` attacks, up to and including the first occupied square in each direction.
+pub fn rook_attacks(square: Square, occupied: Bitboard) -> Bitboard {
+
+    magic::rook_attacks(square, occupied)
+}
+
+/// The squares a bishop on `square` attacks, up to and including the first occupied square in each direction.
+pub fn bishop_attacks(square: Square, occupied: Bitboard) -> Bitboard {
+
+    magic::bishop_attacks(square, occupied)
+}
+
+/// The squares a queen on `square` attacks, which are those of a rook and a bishop together.
+pub fn queen_attacks(square: Square, occupied: Bitboard) -> Bitboard {
+
+    rook_attacks(square, occupied) | bishop_attacks(square, occupied)
+}
+
+/// The squares strictly between two squares on the same rank, file or diagonal, or no squares if they are not on one line.
+pub fn between(from: Square, to: Square) -> Bitboard {
+
+    let (from_bit, to_bit) = (Bitboard::from(from), Bitboard::from(to));
+
+    //Each square blocks the other one, so only the squares on the line between them are attacked from both
+    if rook_attacks(from, Bitboard::EMPTY).contains(to) {
+        rook_attacks(from, to_bit) & rook_attacks(to, from_bit)
+    }
+    else if bishop_attacks(from, Bitboard::EMPTY).contains(to) {
+        bishop_attacks(from, to_bit) & bishop_attacks(to, from_bit)
+    }
+    else {
+        Bitboard::EMPTY
+    }
+}
+
+pub(crate) fn rook_rays(square: Square, occupied: Bitboard) -> Bitboard { //The same as rook_attacks but slow, used to make and check the magic tables
+
+    slide(square, &ROOK_DIRECTIONS, occupied)
+}
+
+pub(crate) fn bishop_rays(square: Square, occupied: Bitboard) -> Bitboard { //The same as bishop_attacks but slow, used to make and check the magic tables
+
+    slide(square, &BISHOP_DIRECTIONS, occupied)
+}
+
+fn slide(square: Square, directions: &[(i32, i32)], occupied: Bitboard) -> Bitboard { //Walks in each direction until the edge of the board or a piece, which can be captured
+
+    let mut attacks = Bitboard::EMPTY;
+
+    for &(files, ranks) in directions {
+
+        let mut next = square.offset(files, ranks);
+
+        while let Some(to) = next {
+
+            attacks |= Bitboard::from(to);
+
+            if occupied.contains(to) {
+                break;
+            }
+
+            next = to.offset(files, ranks);
+        }
+    }
+
+    attacks
+}
+
+
+
+
+// --------------------------
+// ######### TESTS ##########
+// --------------------------
+
+#[cfg(test)]
+mod tests {
+    use crate::{Color, Square};
+
+    use super::{between, bishop_attacks, king_attacks, knight_attacks, pawn_attacks, queen_attacks, rook_attacks, Bitboard};
+
+    fn squares(bitboard: Bitboard) -> Vec<Square> {
+        bitboard.into_iter().collect()
+    }
+
+    #[test]
+    fn sets_of_squares() {
+
+        let bitboard = Bitboard::from(Square::E4) | Bitboard::from(Square::A1) | Bitboard::from(Square::H8);
+
+        assert_eq!(bitboard.count(), 3);
+        assert!(bitboard.contains(Square::E4));
+        assert!(!bitboard.contains(Square::E5));
+        assert_eq!(bitboard.first(), Some(Square::A1));
+        assert_eq!(squares(bitboard), [Square::A1, Square::E4, Square::H8]);
+        assert_eq!(squares(bitboard & !Bitboard::from(Square::E4)), [Square::A1, Square::H8]);
+
+        assert!(Bitboard::EMPTY.is_empty());
+        assert_eq!(Bitboard::EMPTY.first(), None);
+        assert_eq!(Bitboard::FULL.into_iter().count(), 64);
+    }
+
+    #[test]
+    fn step_attacks_stay_on_the_board() {
+
+        assert_eq!(squares(knight_attacks(Square::A1)), [Square::C2, Square::B3]);
+        assert_eq!(knight_attacks(Square::E4).count(), 8);
+        assert_eq!(knight_attacks(Square::H8).count(), 2);
+
+        assert_eq!(squares(king_attacks(Square::A1)), [Square::B1, Square::A2, Square::B2]);
+        assert_eq!(king_attacks(Square::E4).count(), 8);
+
+        assert_eq!(squares(pawn_attacks(Square::E4, Color::White)), [Square::D5, Square::F5]);
+        assert_eq!(squares(pawn_attacks(Square::E4, Color::Black)), [Square::D3, Square::F3]);
+        assert_eq!(squares(pawn_attacks(Square::A2, Color::White)), [Square::B3]);
+        assert_eq!(squares(pawn_attacks(Square::H7, Color::Black)), [Square::G6]);
+    }
+
+    #[test]
+    fn slides_stop_at_the_first_piece() {
+
+        let occupied = Bitboard::from(Square::E6) | Bitboard::from(Square::C4) | Bitboard::from(Square::G2);
+
+        assert_eq!(squares(rook_attacks(Square::E4, occupied)), [
+            Square::E1, Square::E2, Square::E3, Square::C4, Square::D4, Square::F4, Square::G4, Square::H4, Square::E5, Square::E6,
+        ]);
+
+        assert_eq!(squares(bishop_attacks(Square::E4, occupied)), [
+            Square::B1, Square::C2, Square::G2, Square::D3, Square::F3, Square::D5, Square::F5, Square::C6, Square::G6, Square::B7, Square::H7, Square::A8,
+        ]);
+
+        assert_eq!(queen_attacks(Square::E4, occupied), rook_attacks(Square::E4, occupied) | bishop_attacks(Square::E4, occupied));
+        assert_eq!(rook_attacks(Square::A1, Bitboard::EMPTY).count(), 14);
+        assert_eq!(bishop_attacks(Square::D4, Bitboard::FULL).count(), 4);
+    }
+
+    #[test]
+    fn squares_between_two_squares() {
+
+        assert_eq!(squares(between(Square::E1, Square::E4)), [Square::E2, Square::E3]);
+        assert_eq!(squares(between(Square::H8, Square::E5)), [Square::F6, Square::G7]);
+        assert_eq!(squares(between(Square::A1, Square::D1)), [Square::B1, Square::C1]);
+        assert_eq!(between(Square::E1, Square::E2), Bitboard::EMPTY);
+        assert_eq!(between(Square::E1, Square::F3), Bitboard::EMPTY);
+    }
+}
@@ -0,0 +1,682 @@
+//! Scoring a position without searching, for the positions at the end of the lines the search looks at.
+//!
+//! The score adds up the material, where each piece stands, the pawn structure, the safety of the kings and
+//! how many squares the pieces can go to. Every term has one weight for the middlegame and one for the endgame,
+//! and the two scores are mixed by how much material is left: with all the pieces on the board only the
+//! middlegame counts, with only kings and pawns only the endgame does.
+//!
+//! The weights can be read from a config so they can be tuned without compiling, see [`EvalWeights::from_config`].
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::bitboard::{self, Bitboard};
+use crate::{Board, Color, Game, PieceType, Square};
+
+
+const PIECE_TYPES: [PieceType; 6] = [PieceType::Pawn, PieceType::Rook, PieceType::Knight, PieceType::Bishop, PieceType::Queen, PieceType::King];
+const PIECE_NAMES: [&str; 6] = ["pawn", "rook", "knight", "bishop", "queen", "king"]; //In the same order as PieceType
+
+const PHASE_WEIGHTS: [i32; 6] = [0, 2, 1, 1, 4, 0]; //How much each piece counts towards the middlegame, indexed by PieceType
+const MAX_PHASE: i32 = 24; //All the pieces of the starting position
+
+const FILE_A: u64 = 0x0101_0101_0101_0101;
+
+
+/// The weights of one phase of the game, in centipawns. The arrays indexed by piece are in the order of [`PieceType`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct PhaseWeights {
+    pub piece_values: [i32; 6],
+    /// The bonus for a piece standing on a square, written the way the board is printed from white's side,
+    /// with a8 first and h1 last. The tables are mirrored for black.
+    pub tables: [[i32; 64]; 6],
+    /// For each pawn on a file after the first one.
+    pub doubled_pawn: i32,
+    /// For each pawn without pawns of its color on the files beside it.
+    pub isolated_pawn: i32,
+    /// For a pawn that no pawn of the opponent can stop, by the rank it is on counted from its own side.
+    pub passed_pawn: [i32; 8],
+    /// For each pawn in the two ranks in front of its king and on the files next to it.
+    pub king_shield: i32,
+    /// For each square next to the opponents king, or the king itself, attacked by a piece other than a pawn.
+    pub king_attack: i32,
+    /// For each square a piece attacks that does not have a piece of its own color on it.
+    pub mobility: [i32; 6],
+}
+
+/// The weights for the middlegame and the endgame. [`EvalWeights::default`] gives the weights [`evaluate`] uses.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EvalWeights {
+    pub middlegame: PhaseWeights,
+    pub endgame: PhaseWeights,
+}
+
+/// Why a config could not be read by [`EvalWeights::from_config`]. `line` counts the lines from 1.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EvalConfigError {
+    pub line: usize,
+    pub kind: EvalConfigErrorKind,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum EvalConfigErrorKind {
+    /// A section other than `[middlegame]` and `[endgame]`.
+    UnknownSection(String),
+    /// A weight is given before the first section.
+    MissingSection,
+    /// There is no weight with the name.
+    UnknownWeight(String),
+    /// Numbers are given without a name in front of them.
+    MissingName,
+    /// Something that is not a whole number where the numbers of a weight should be.
+    InvalidNumber(String),
+    /// A weight is given the wrong amount of numbers.
+    WrongCount { expected: usize, found: usize },
+}
+
+impl fmt::Display for EvalConfigError {
+
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+
+        write!(f, "line {}: ", self.line)?;
+
+        match &self.kind {
+            EvalConfigErrorKind::UnknownSection(name) => write!(f, "unknown section [{}], expected [middlegame] or [endgame]", name),
+            EvalConfigErrorKind::MissingSection => write!(f, "weights have to be in a [middlegame] or [endgame] section"),
+            EvalConfigErrorKind::UnknownWeight(name) => write!(f, "there is no weight called \"{}\"", name),
+            EvalConfigErrorKind::MissingName => write!(f, "numbers without the name of a weight"),
+            EvalConfigErrorKind::InvalidNumber(text) => write!(f, "\"{}\" is not a whole number", text),
+            EvalConfigErrorKind::WrongCount { expected, found } => write!(f, "expected {} numbers but found {}", expected, found),
+        }
+    }
+}
+
+impl std::error::Error for EvalConfigError {}
+
+
+impl PhaseWeights {
+
+    fn entries(&mut self) -> Vec<(String, &mut [i32])> { //Every weight by the name it has in a config, in the order they are written
+
+        let mut entries: Vec<(String, &mut [i32])> = Vec::new();
+        let mut mobility = Vec::new();
+
+        let pieces = PIECE_NAMES.iter().zip(self.piece_values.iter_mut()).zip(self.tables.iter_mut()).zip(self.mobility.iter_mut());
+
+        for (((name, value), table), mobility_weight) in pieces {
+
+            entries.push((format!("{}_value", name), std::slice::from_mut(value)));
+            entries.push((format!("{}_table", name), table));
+
+            if !matches!(*name, "pawn" | "king") { //Pawns and kings are not counted for mobility
+                mobility.push((format!("{}_mobility", name), std::slice::from_mut(mobility_weight)));
+            }
+        }
+
+        entries.push(("doubled_pawn".to_string(), std::slice::from_mut(&mut self.doubled_pawn)));
+        entries.push(("isolated_pawn".to_string(), std::slice::from_mut(&mut self.isolated_pawn)));
+        entries.push(("passed_pawn".to_string(), &mut self.passed_pawn));
+        entries.push(("king_shield".to_string(), std::slice::from_mut(&mut self.king_shield)));
+        entries.push(("king_attack".to_string(), std::slice::from_mut(&mut self.king_attack)));
+        entries.extend(mobility);
+
+        entries
+    }
+
+    fn score(&self, features: &Features) -> i32 { //The score of one side in this phase
+
+        let mut score = 0;
+
+        for (index, squares) in features.pieces.iter().enumerate() {
+            for square in *squares {
+                score += self.piece_values[index] + self.tables[index][square.index()];
+            }
+        }
+
+        score += features.doubled_pawns * self.doubled_pawn;
+        score += features.isolated_pawns * self.isolated_pawn;
+        score += features.passed_pawns.iter().zip(self.passed_pawn).map(|(count, weight)| count * weight).sum::<i32>();
+        score += features.king_shield * self.king_shield;
+        score += features.king_attacks * self.king_attack;
+        score += features.mobility.iter().zip(self.mobility).map(|(count, weight)| count * weight).sum::<i32>();
+
+        score
+    }
+}
+
+
+impl EvalWeights {
+
+    /// Reads weights from a config, starting from the default weights so only the ones that change have to be given.
+    ///
+    /// The config has a `[middlegame]` and an `[endgame]` section with lines like `pawn_value = 100`, and
+    /// everything after a `#` is a comment. A weight with more than one number, like `knight_table`, can go on
+    /// over the following lines. [`EvalWeights::to_config`] writes every weight with its name.
+    pub fn from_config(text: &str) -> Result<EvalWeights, EvalConfigError> {
+
+        let mut sections: [HashMap<String, (usize, Vec<i32>)>; 2] = Default::default(); //The numbers of the middlegame and endgame weights, with the line their name is on
+        let mut section = None;
+        let mut current: Option<String> = None;
+
+        for (index, line) in text.lines().enumerate() {
+
+            let line_number = index + 1;
+            let error = |kind| EvalConfigError { line: line_number, kind };
+
+            let line = line.split('#').next().unwrap_or_default().trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(name) = line.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')) {
+
+                section = match name.trim() {
+                    "middlegame" => Some(0),
+                    "endgame" => Some(1),
+                    _ => return Err(error(EvalConfigErrorKind::UnknownSection(name.to_string()))),
+                };
+
+                current = None;
+                continue;
+            }
+
+            let Some(weights) = section.map(|section| &mut sections[section]) else {
+                return Err(error(EvalConfigErrorKind::MissingSection));
+            };
+
+            let numbers = match line.split_once('=') {
+
+                Some((name, numbers)) => {
+
+                    let name = name.trim().to_string();
+
+                    weights.insert(name.clone(), (line_number, Vec::new()));
+                    current = Some(name);
+
+                    numbers
+                }
+
+                None => line, //The numbers go on from the line before
+            };
+
+            let Some((_, values)) = current.as_ref().and_then(|name| weights.get_mut(name)) else {
+                return Err(error(EvalConfigErrorKind::MissingName));
+            };
+
+            for number in numbers.split_whitespace() {
+                values.push(number.parse().map_err(|_| error(EvalConfigErrorKind::InvalidNumber(number.to_string())))?);
+            }
+        }
+
+        let mut weights = EvalWeights::default();
+
+        for (phase, mut given) in [&mut weights.middlegame, &mut weights.endgame].into_iter().zip(sections) {
+
+            for (name, slot) in phase.entries() {
+
+                let Some((line, values)) = given.remove(&name) else { continue };
+
+                if values.len() != slot.len() {
+                    return Err(EvalConfigError { line, kind: EvalConfigErrorKind::WrongCount { expected: slot.len(), found: values.len() } });
+                }
+
+                slot.copy_from_slice(&values);
+            }
+
+            if let Some((name, (line, _))) = given.into_iter().min_by_key(|(_, (line, _))| *line) { //Anything left over does not have a weight to go to
+                return Err(EvalConfigError { line, kind: EvalConfigErrorKind::UnknownWeight(name) });
+            }
+        }
+
+        Ok(weights)
+    }
+
+    /// Writes every weight in the format read by [`EvalWeights::from_config`], with the tables in eight rows.
+    pub fn to_config(&self) -> String {
+
+        let mut weights = self.clone(); //Getting the weights by name needs them to be mutable
+        let mut text = String::new();
+
+        for (section, phase) in [("middlegame", &mut weights.middlegame), ("endgame", &mut weights.endgame)] {
+
+            if !text.is_empty() {
+                text.push('\n');
+            }
+
+            text.push_str(&format!("[{}]\n", section));
+
+            for (name, values) in phase.entries() {
+
+                let numbers: Vec<String> = values.iter().map(|value| value.to_string()).collect();
+
+                if numbers.len() == 64 {
+
+                    text.push_str(&format!("{} =\n", name));
+
+                    for row in numbers.chunks(8) {
+                        text.push_str(&format!("    {}\n", row.join(" ")));
+                    }
+                }
+                else {
+                    text.push_str(&format!("{} = {}\n", name, numbers.join(" ")));
+                }
+            }
+        }
+
+        text
+    }
+}
+
+impl Default for EvalWeights {
+
+    fn default() -> Self {
+        DEFAULT_WEIGHTS
+    }
+}
+
+
+/// Scores the position for the player to move with the default weights, in centipawns.
+/// A position where neither player can checkmate scores 0.
+pub fn evaluate(game: &Game) -> i32 {
+
+    evaluate_with(game, &DEFAULT_WEIGHTS)
+}
+
+/// Like [`evaluate`] but with the given weights.
+pub fn evaluate_with(game: &Game, weights: &EvalWeights) -> i32 {
+
+    if game.is_insufficient_material() {
+        return 0;
+    }
+
+    let board = &game.board;
+
+    let phase = PIECE_TYPES.iter()
+        .map(|&piece_type| (board.pieces[piece_type as usize].count() as i32) * PHASE_WEIGHTS[piece_type as usize])
+        .sum::<i32>()
+        .min(MAX_PHASE); //Promotions can give more pieces than at the start
+
+    let (white, black) = (features(board, Color::White), features(board, Color::Black));
+
+    let middlegame = weights.middlegame.score(&white) - weights.middlegame.score(&black);
+    let endgame = weights.endgame.score(&white) - weights.endgame.score(&black);
+
+    let score = (middlegame * phase + endgame * (MAX_PHASE - phase)) / MAX_PHASE;
+
+    match game.player_color() {
+        Color::White => score,
+        Color::Black => -score,
+    }
+}
+
+
+struct Features { //What the weights are multiplied with for one side
+    pieces: [Bitboard; 6], //The squares of the pieces as seen from their own side, which is where they are looked up in the tables
+    doubled_pawns: i32,
+    isolated_pawns: i32,
+    passed_pawns: [i32; 8],
+    king_shield: i32,
+    king_attacks: i32,
+    mobility: [i32; 6],
+}
+
+fn features(board: &Board, color: Color) -> Features {
+
+    let opponent = color.opponent();
+
+    let forward = match color {
+        Color::White => 1,
+        Color::Black => -1,
+    };
+
+    let own = board.get_color_pieces(color);
+    let occupied = board.get_occupied();
+    let pawns = board.get_pieces(color, PieceType::Pawn);
+    let enemy_pawns = board.get_pieces(opponent, PieceType::Pawn);
+
+    let relative_rank = |square: Square| match color {
+        Color::White => square.rank().index() as usize,
+        Color::Black => 7 - square.rank().index() as usize,
+    };
+
+    let mut pieces = [Bitboard::EMPTY; 6];
+
+    for piece_type in PIECE_TYPES {
+
+        let squares = board.get_pieces(color, piece_type).0;
+
+        //The tables start at a8, which is a1 flipped over for white. For black a8 is already their own first rank
+        pieces[piece_type as usize] = match color {
+            Color::White => Bitboard(squares.swap_bytes()),
+            Color::Black => Bitboard(squares),
+        };
+    }
+
+    let mut doubled_pawns = 0;
+    let mut isolated_pawns = 0;
+
+    for file in 0..8 {
+
+        let on_file = (pawns & Bitboard(FILE_A << file)).count() as i32;
+
+        let neighbours = match file {
+            0 => FILE_A << 1,
+            7 => FILE_A << 6,
+            _ => (FILE_A << (file - 1)) | (FILE_A << (file + 1)),
+        };
+
+        doubled_pawns += (on_file - 1).max(0);
+
+        if (pawns & Bitboard(neighbours)).is_empty() {
+            isolated_pawns += on_file;
+        }
+    }
+
+    let mut passed_pawns = [0; 8];
+
+    for pawn in pawns {
+
+        let stopped = enemy_pawns.into_iter().any(|enemy| { //An enemy pawn ahead on the same file or the files beside it can block or capture the pawn
+            enemy.file().index().abs_diff(pawn.file().index()) <= 1 && relative_rank(enemy) > relative_rank(pawn)
+        });
+
+        if !stopped {
+            passed_pawns[relative_rank(pawn)] += 1;
+        }
+    }
+
+    let mut king_shield = 0;
+
+    if let Some(king) = board.get_pieces(color, PieceType::King).first() {
+        for (files, ranks) in [(-1, 1), (0, 1), (1, 1), (-1, 2), (0, 2), (1, 2)] {
+            if king.offset(files, ranks * forward).is_some_and(|square| pawns.contains(square)) {
+                king_shield += 1;
+            }
+        }
+    }
+
+    let enemy_king_zone = match board.get_pieces(opponent, PieceType::King).first() {
+        Some(king) => bitboard::king_attacks(king) | Bitboard::from(king),
+        None => Bitboard::EMPTY,
+    };
+
+    let mut king_attacks = 0;
+    let mut mobility = [0; 6];
+
+    for piece_type in [PieceType::Knight, PieceType::Bishop, PieceType::Rook, PieceType::Queen] {
+        for square in board.get_pieces(color, piece_type) {
+
+            let attacks = match piece_type {
+                PieceType::Knight => bitboard::knight_attacks(square),
+                PieceType::Bishop => bitboard::bishop_attacks(square, occupied),
+                PieceType::Rook => bitboard::rook_attacks(square, occupied),
+                _ => bitboard::queen_attacks(square, occupied),
+            };
+
+            mobility[piece_type as usize] += (attacks & !own).count() as i32;
+            king_attacks += (attacks & enemy_king_zone).count() as i32;
+        }
+    }
+
+    Features { pieces, doubled_pawns, isolated_pawns, passed_pawns, king_shield, king_attacks, mobility }
+}
+
+
+const DEFAULT_WEIGHTS: EvalWeights = EvalWeights {
+    middlegame: PhaseWeights {
+        piece_values: [100, 500, 320, 330, 900, 0],
+        tables: [PAWN_MIDDLEGAME, ROOK_TABLE, KNIGHT_TABLE, BISHOP_TABLE, QUEEN_TABLE, KING_MIDDLEGAME],
+        doubled_pawn: -10,
+        isolated_pawn: -10,
+        passed_pawn: [0, 5, 10, 15, 25, 40, 60, 0],
+        king_shield: 10,
+        king_attack: 5,
+        mobility: [0, 2, 4, 5, 1, 0],
+    },
+    endgame: PhaseWeights {
+        piece_values: [120, 530, 300, 320, 950, 0],
+        tables: [PAWN_ENDGAME, ROOK_TABLE, KNIGHT_TABLE, BISHOP_TABLE, QUEEN_TABLE, KING_ENDGAME],
+        doubled_pawn: -20,
+        isolated_pawn: -15,
+        passed_pawn: [0, 10, 15, 25, 45, 75, 120, 0],
+        king_shield: 0,
+        king_attack: 1,
+        mobility: [0, 4, 4, 5, 2, 0],
+    },
+};
+
+const PAWN_MIDDLEGAME: [i32; 64] = [
+     0,   0,   0,   0,   0,   0,   0,   0,
+    50,  50,  50,  50,  50,  50,  50,  50,
+    10,  10,  20,  30,  30,  20,  10,  10,
+     5,   5,  10,  25,  25,  10,   5,   5,
+     0,   0,   0,  20,  20,   0,   0,   0,
+     5,  -5, -10,   0,   0, -10,  -5,   5,
+     5,  10,  10, -20, -20,  10,  10,   5,
+     0,   0,   0,   0,   0,   0,   0,   0,
+];
+
+const PAWN_ENDGAME: [i32; 64] = [ //In the endgame every step closer to promoting counts, wherever the pawn is
+     0,   0,   0,   0,   0,   0,   0,   0,
+    50,  50,  50,  50,  50,  50,  50,  50,
+    30,  30,  30,  30,  30,  30,  30,  30,
+    20,  20,  20,  20,  20,  20,  20,  20,
+    10,  10,  10,  10,  10,  10,  10,  10,
+     5,   5,   5,   5,   5,   5,   5,   5,
+     0,   0,   0,   0,   0,   0,   0,   0,
+     0,   0,   0,   0,   0,   0,   0,   0,
+];
+
+const KNIGHT_TABLE: [i32; 64] = [
+   -50, -40, -30, -30, -30, -30, -40, -50,
+   -40, -20,   0,   0,   0,   0, -20, -40,
+   -30,   0,  10,  15,  15,  10,   0, -30,
+   -30,   5,  15,  20,  20,  15,   5, -30,
+   -30,   0,  15,  20,  20,  15,   0, -30,
+   -30,   5,  10,  15,  15,  10,   5, -30,
+   -40, -20,   0,   5,   5,   0, -20, -40,
+   -50, -40, -30, -30, -30, -30, -40, -50,
+];
+
+const BISHOP_TABLE: [i32; 64] = [
+   -20, -10, -10, -10, -10, -10, -10, -20,
+   -10,   0,   0,   0,   0,   0,   0, -10,
+   -10,   0,   5,  10,  10,   5,   0, -10,
+   -10,   5,   5,  10,  10,   5,   5, -10,
+   -10,   0,  10,  10,  10,  10,   0, -10,
+   -10,  10,  10,  10,  10,  10,  10, -10,
+   -10,   5,   0,   0,   0,   0,   5, -10,
+   -20, -10, -10, -10, -10, -10, -10, -20,
+];
+
+const ROOK_TABLE: [i32; 64] = [
+     0,   0,   0,   0,   0,   0,   0,   0,
+     5,  10,  10,  10,  10,  10,  10,   5,
+    -5,   0,   0,   0,   0,   0,   0,  -5,
+    -5,   0,   0,   0,   0,   0,   0,  -5,
+    -5,   0,   0,   0,   0,   0,   0,  -5,
+    -5,   0,   0,   0,   0,   0,   0,  -5,
+    -5,   0,   0,   0,   0,   0,   0,  -5,
+     0,   0,   0,   5,   5,   0,   0,   0,
+];
+
+const QUEEN_TABLE: [i32; 64] = [
+   -20, -10, -10,  -5,  -5, -10, -10, -20,
+   -10,   0,   0,   0,   0,   0,   0, -10,
+   -10,   0,   5,   5,   5,   5,   0, -10,
+    -5,   0,   5,   5,   5,   5,   0,  -5,
+     0,   0,   5,   5,   5,   5,   0,  -5,
+   -10,   5,   5,   5,   5,   5,   0, -10,
+   -10,   0,   5,   0,   0,   0,   0, -10,
+   -20, -10, -10,  -5,  -5, -10, -10, -20,
+];
+
+const KING_MIDDLEGAME: [i32; 64] = [ //The king hides behind its pawns in a corner while there are pieces to attack it
+   -30, -40, -40, -50, -50, -40, -40, -30,
+   -30, -40, -40, -50, -50, -40, -40, -30,
+   -30, -40, -40, -50, -50, -40, -40, -30,
+   -30, -40, -40, -50, -50, -40, -40, -30,
+   -20, -30, -30, -40, -40, -30, -30, -20,
+   -10, -20, -20, -20, -20, -20, -20, -10,
+    20,  20,   0,   0,   0,   0,  20,  20,
+    20,  30,  10,   0,   0,  10,  30,  20,
+];
+
+const KING_ENDGAME: [i32; 64] = [ //Without them it belongs in the center
+   -50, -40, -30, -20, -20, -30, -40, -50,
+   -30, -20, -10,   0,   0, -10, -20, -30,
+   -30, -10,  20,  30,  30,  20, -10, -30,
+   -30, -10,  30,  40,  40,  30, -10, -30,
+   -30, -10,  30,  40,  40,  30, -10, -30,
+   -30, -10,  20,  30,  30,  20, -10, -30,
+   -30, -30,   0,   0,   0,   0, -30, -30,
+   -50, -30, -30, -30, -30, -30, -30, -50,
+];
+
+
+
+
+// --------------------------
+// ######### TESTS ##########
+// --------------------------
+
+#[cfg(test)]
+mod tests {
+    use crate::{Game, PieceType};
+
+    use super::{evaluate, evaluate_with, EvalConfigError, EvalConfigErrorKind, EvalWeights};
+
+    // the same position with the colors swapped and the board turned around
+    fn mirror(fen: &str) -> String {
+
+        let fields: Vec<&str> = fen.split(' ').collect();
+
+        let swap_case = |text: &str| -> String {
+            text.chars().map(|c| if c.is_uppercase() { c.to_ascii_lowercase() } else { c.to_ascii_uppercase() }).collect()
+        };
+
+        let placement: Vec<String> = fields[0].split('/').rev().map(swap_case).collect();
+        let side = if fields[1] == "w" { "b" } else { "w" };
+        let en_passant = match fields[3].split_at(1) {
+            (file, "3") => format!("{}6", file),
+            (file, "6") => format!("{}3", file),
+            _ => "-".to_string(),
+        };
+
+        format!("{} {} {} {} {} {}", placement.join("/"), side, swap_case(fields[2]), en_passant, fields[4], fields[5])
+    }
+
+    fn score(fen: &str) -> i32 {
+        evaluate(&Game::from_fen(fen).unwrap())
+    }
+
+    #[test]
+    fn both_colors_are_scored_the_same() {
+
+        assert_eq!(evaluate(&Game::new()), 0);
+
+        for fen in [
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+        ] {
+            assert_eq!(score(fen), score(&mirror(fen)), "{}", fen);
+        }
+    }
+
+    #[test]
+    fn scores_are_for_the_player_to_move() {
+
+        let white = score("4k3/8/8/8/8/8/8/3QK3 w - - 0 1");
+        let black = score("4k3/8/8/8/8/8/8/3QK3 b - - 0 1");
+
+        assert!(white > 800);
+        assert_eq!(white, -black);
+
+        // a king and a knight can not checkmate
+        assert_eq!(score("4k3/8/8/8/8/8/8/3NK3 w - - 0 1"), 0);
+    }
+
+    #[test]
+    fn pawn_structure() {
+
+        // the same pawns, but doubled and isolated on the c file instead of side by side
+        let healthy = score("4k3/pp6/8/8/8/8/1PP5/4K3 w - - 0 1");
+        let doubled = score("4k3/pp6/8/8/8/2P5/2P5/4K3 w - - 0 1");
+
+        assert!(healthy > doubled);
+
+        // a pawn on the sixth rank is worth more with nothing in front of it
+        let passed = score("4k3/8/P7/8/8/8/8/4K3 w - - 0 1");
+        let blocked = score("4k3/1p6/P7/8/8/8/8/4K3 w - - 0 1");
+
+        assert!(passed > blocked + 100); // more than the black pawn is worth
+    }
+
+    #[test]
+    fn the_king_goes_to_the_center_in_the_endgame() {
+
+        // with only pawns left the king belongs in the center
+        assert!(score("4k3/pppp4/8/8/3K4/8/PPPP4/8 w - - 0 1") > score("4k3/pppp4/8/8/8/8/PPPP4/K7 w - - 0 1"));
+
+        // with the queens and rooks on the board it belongs behind its pawns
+        let castled = score("r2qk2r/pppppppp/8/8/8/8/PPPPPPPP/R2Q1RK1 w kq - 0 1");
+        let central = score("r2qk2r/pppppppp/8/8/8/4K3/PPPPPPPP/R2Q3R w kq - 0 1");
+
+        assert!(castled > central);
+    }
+
+    #[test]
+    fn config_round_trip() {
+
+        let weights = EvalWeights::default();
+
+        assert_eq!(EvalWeights::from_config(&weights.to_config()), Ok(weights.clone()));
+        assert_eq!(EvalWeights::from_config(""), Ok(weights));
+    }
+
+    #[test]
+    fn config_changes_the_score() {
+
+        let config = "
+            # pawns are worth nothing
+            [middlegame]
+            pawn_value = 0
+            pawn_table =
+                0 0 0 0 0 0 0 0   0 0 0 0 0 0 0 0   0 0 0 0 0 0 0 0   0 0 0 0 0 0 0 0
+                0 0 0 0 0 0 0 0   0 0 0 0 0 0 0 0   0 0 0 0 0 0 0 0   0 0 0 0 0 0 0 0
+
+            [endgame]
+            pawn_value = 0 # the endgame table is left as it is
+            passed_pawn = 0 0 0 0 0 0 0 0
+        ";
+
+        let weights = EvalWeights::from_config(config).unwrap();
+
+        assert_eq!(weights.middlegame.piece_values[PieceType::Pawn as usize], 0);
+        assert_eq!(weights.endgame.doubled_pawn, EvalWeights::default().endgame.doubled_pawn);
+
+        let game = Game::from_fen("4k3/8/8/8/8/8/PPP5/4K3 w - - 0 1").unwrap();
+
+        assert!(evaluate(&game) > 300);
+        assert!(evaluate_with(&game, &weights) < 50);
+    }
+
+    #[test]
+    fn config_errors() {
+
+        let error = |line, kind| Err(EvalConfigError { line, kind });
+
+        assert_eq!(EvalWeights::from_config("pawn_value = 1"), error(1, EvalConfigErrorKind::MissingSection));
+        assert_eq!(EvalWeights::from_config("[opening]"), error(1, EvalConfigErrorKind::UnknownSection("opening".to_string())));
+        assert_eq!(EvalWeights::from_config("[endgame]\n\npawn_valeu = 1"), error(3, EvalConfigErrorKind::UnknownWeight("pawn_valeu".to_string())));
+        assert_eq!(EvalWeights::from_config("[endgame]\n1 2 3"), error(2, EvalConfigErrorKind::MissingName));
+        assert_eq!(EvalWeights::from_config("[endgame]\npawn_value = 1.5"), error(2, EvalConfigErrorKind::InvalidNumber("1.5".to_string())));
+
+        let too_few = EvalWeights::from_config("[middlegame]\nknight_table = 1 2\n  3 4");
+
+        assert_eq!(too_few, error(2, EvalConfigErrorKind::WrongCount { expected: 64, found: 4 }));
+        assert_eq!(too_few.unwrap_err().to_string(), "line 2: expected 64 numbers but found 4");
+    }
+}
@@ -0,0 +1,165 @@
+//! Static exchange evaluation: what a capture wins or loses if both players keep capturing on the square,
+//! each with their least valuable piece, and either of them can stop when going on would lose more.
+//!
+//! Only the pieces attacking the square are looked at, so pins and checks elsewhere on the board are not noticed.
+//! Rooks, bishops and queens behind a capturing piece join in once it has left.
+
+use crate::{Bitboard, Color, Game, Move, PieceType, Rank, Square};
+
+
+const VALUES: [i32; 6] = [100, 500, 320, 330, 900, 20_000]; //Indexed by PieceType. The king is worth more than anything it could capture
+const CHEAPEST_FIRST: [PieceType; 6] = [PieceType::Pawn, PieceType::Knight, PieceType::Bishop, PieceType::Rook, PieceType::Queen, PieceType::King];
+
+
+impl Game {
+
+    /// The material the player to move wins with the move if both players go on capturing on its
+    /// destination for as long as it pays off, in centipawns. It is negative if the piece is lost for less than it is worth.
+    ///
+    /// A move that does not capture scores 0 unless the piece can be taken for free, and a promotion counts
+    /// the promoted piece. Whether the move is legal is not checked.
+    pub fn static_exchange_eval(&self, mv: Move) -> i32 {
+
+        let board = &self.board;
+
+        let Some(piece) = board.get_piece(mv.from) else { return 0 };
+
+        let mut occupied = board.get_occupied() & !Bitboard::from(mv.from);
+
+        let mut gains = Vec::with_capacity(32); //What the side capturing has won after each capture, if the other side stopped there
+
+        let en_passant = piece.piece_type == PieceType::Pawn && mv.from.file() != mv.to.file() && board.get_piece(mv.to).is_none();
+
+        let captured = match en_passant {
+            true => {
+                occupied &= !Bitboard::from(Square::from_file_rank(mv.to.file(), mv.from.rank())); //The pawn beside can no longer defend or attack the square
+                VALUES[PieceType::Pawn as usize]
+            }
+            false => board.get_piece(mv.to).map_or(0, |captured| VALUES[captured.piece_type as usize]),
+        };
+
+        let mut on_square = piece.piece_type; //The piece the next capture takes
+
+        if piece.piece_type == PieceType::Pawn && mv.to.rank() == last_rank(piece.color) {
+
+            on_square = mv.promotion.unwrap_or(self.promotion);
+            gains.push(captured + VALUES[on_square as usize] - VALUES[PieceType::Pawn as usize]);
+        }
+        else {
+            gains.push(captured);
+        }
+
+        let mut side = piece.color.opponent();
+
+        loop {
+
+            let attackers = board.attackers_with(mv.to, occupied); //Worked out again after every capture, to find the pieces that were behind the one that captured
+
+            let Some((piece_type, from)) = CHEAPEST_FIRST.iter().find_map(|&piece_type| {
+                (attackers & board.get_color_pieces(side) & board.pieces[piece_type as usize]).first().map(|square| (piece_type, square))
+            }) else { break };
+
+            let opponent = side.opponent();
+
+            if piece_type == PieceType::King && !(attackers & board.get_color_pieces(opponent)).is_empty() {
+                break; //The king can not capture a defended piece
+            }
+
+            let promotion = match piece_type == PieceType::Pawn && mv.to.rank() == last_rank(side) { //A pawn taking back on the last rank becomes a queen
+                true => VALUES[PieceType::Queen as usize] - VALUES[PieceType::Pawn as usize],
+                false => 0,
+            };
+
+            gains.push(VALUES[on_square as usize] + promotion - gains[gains.len() - 1]);
+
+            occupied &= !Bitboard::from(from);
+            on_square = if promotion > 0 { PieceType::Queen } else { piece_type };
+            side = opponent;
+        }
+
+        //Going back from the last capture, each side only captures if it is better than stopping
+        while gains.len() > 1 {
+
+            let last = gains.pop().unwrap_or_default();
+            let previous = gains.len() - 1;
+
+            gains[previous] = -(-gains[previous]).max(last);
+        }
+
+        gains[0]
+    }
+}
+
+fn last_rank(color: Color) -> Rank { //The rank a pawn of the color promotes on
+
+    match color {
+        Color::White => Rank::Eighth,
+        Color::Black => Rank::First,
+    }
+}
+
+
+
+
+// --------------------------
+// ######### TESTS ##########
+// --------------------------
+
+#[cfg(test)]
+mod tests {
+    use crate::{Game, Move, PieceType, Square};
+
+    fn see(fen: &str, mv: Move) -> i32 {
+        Game::from_fen(fen).unwrap().static_exchange_eval(mv)
+    }
+
+    #[test]
+    fn free_and_defended_pieces() {
+
+        let d2d5 = Move::new(Square::D2, Square::D5);
+
+        assert_eq!(see("4k3/8/8/3p4/8/8/3R4/4K3 w - - 0 1", d2d5), 100);
+        assert_eq!(see("4k3/8/2p5/3p4/8/8/3R4/4K3 w - - 0 1", d2d5), -400);
+
+        // a defended rook is still worth taking with a pawn
+        assert_eq!(see("4k3/8/8/2r5/1P6/8/8/4K3 w - - 0 1", Move::new(Square::B4, Square::C5)), 500);
+
+        // moving to an empty square the opponent attacks loses the piece
+        assert_eq!(see("4k3/8/2p5/8/8/8/3R4/4K3 w - - 0 1", d2d5), -500);
+        assert_eq!(see("4k3/8/8/8/8/8/3R4/4K3 w - - 0 1", d2d5), 0);
+    }
+
+    #[test]
+    fn pieces_behind_join_in() {
+
+        // Rxd5 Rxd5 Rxd5 wins the pawn with the second rook
+        assert_eq!(see("3rk3/8/8/3p4/8/8/3R4/3RK3 w - - 0 1", Move::new(Square::D2, Square::D5)), 100);
+
+        // with a bishop behind the pawn black comes out ahead
+        assert_eq!(see("3rk3/5b2/8/3p4/8/8/3R4/3RK3 w - - 0 1", Move::new(Square::D2, Square::D5)), -400);
+    }
+
+    #[test]
+    fn king_only_takes_undefended_pieces() {
+
+        let qxd7 = Move::new(Square::D2, Square::D7);
+
+        assert_eq!(see("4k3/3p4/8/8/8/8/3Q4/4K3 w - - 0 1", qxd7), -800);
+        assert_eq!(see("4k3/3p4/8/8/8/8/3Q4/3RK3 w - - 0 1", qxd7), 100); // the rook behind the queen defends d7
+    }
+
+    #[test]
+    fn en_passant_and_promotions() {
+
+        assert_eq!(see("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1", Move::new(Square::E5, Square::D6)), 100);
+
+        assert_eq!(see("4k3/1P6/8/8/8/8/8/4K3 w - - 0 1", Move::with_promotion(Square::B7, Square::B8, PieceType::Queen)), 800);
+        assert_eq!(see("r3k3/1P6/8/8/8/8/8/4K3 w - - 0 1", Move::with_promotion(Square::B7, Square::B8, PieceType::Queen)), -100);
+        assert_eq!(see("r3k3/1P6/8/8/8/8/8/4K3 w - - 0 1", Move::with_promotion(Square::B7, Square::A8, PieceType::Queen)), 1300);
+        assert_eq!(see("4k3/1P6/8/8/8/8/8/4K3 w - - 0 1", Move::with_promotion(Square::B7, Square::B8, PieceType::Knight)), 220);
+
+        // the pawn taking back on b8 becomes a queen, and with a rook on h8 black can take the queen too
+        assert_eq!(see("1N2r3/P7/8/7k/8/8/8/7K b - - 0 1", Move::new(Square::E8, Square::B8)), -980);
+        assert_eq!(see("1N2r2r/P7/8/7k/8/8/8/7K b - - 0 1", Move::new(Square::E8, Square::B8)), -80);
+    }
+}
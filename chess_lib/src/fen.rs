@@ -0,0 +1,409 @@
+//! Reading and writing positions in Forsyth-Edwards Notation (FEN), for example the starting position:
+//!
+//! `rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1`
+
+use std::fmt;
+
+use crate::{Board, CastlingRights, Color, Game, Piece, PieceType, Player, Rank, Square};
+
+
+/// The reasons a FEN string can be refused by [`Game::from_fen`]. Every variant except
+/// `FieldCount` names the field that is wrong and describes what is wrong with it.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FenError {
+    /// The FEN does not have six fields separated by spaces.
+    FieldCount(usize),
+    /// The first field, the pieces on the board.
+    PiecePlacement(String),
+    /// The second field, "w" or "b".
+    SideToMove(String),
+    /// The third field, "-" or some of "KQkq".
+    Castling(String),
+    /// The fourth field, "-" or the square a pawn skipped with its last move.
+    EnPassant(String),
+    /// The fifth field, the number of moves since the last capture or pawn move.
+    HalfmoveClock(String),
+    /// The sixth field, the number of the current full move.
+    FullmoveNumber(String),
+}
+
+impl fmt::Display for FenError {
+
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+
+        match self {
+            FenError::FieldCount(count) => write!(f, "a FEN has 6 fields but {} were given", count),
+            FenError::PiecePlacement(reason) => write!(f, "invalid piece placement: {}", reason),
+            FenError::SideToMove(reason) => write!(f, "invalid side to move: {}", reason),
+            FenError::Castling(reason) => write!(f, "invalid castling rights: {}", reason),
+            FenError::EnPassant(reason) => write!(f, "invalid en passant square: {}", reason),
+            FenError::HalfmoveClock(reason) => write!(f, "invalid halfmove clock: {}", reason),
+            FenError::FullmoveNumber(reason) => write!(f, "invalid fullmove number: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for FenError {}
+
+
+impl Game {
+
+    /// Sets up a game from a position in FEN.
+    ///
+    /// All six fields are required and checked strictly: each side needs exactly one king, pawns can
+    /// not stand on the first or last rank, the player who just moved can not be in check, castling
+    /// rights need the king and rook on their starting squares and an en passant square needs the pawn
+    /// that skipped it.
+    pub fn from_fen(fen: &str) -> Result<Game, FenError> {
+
+        let fields: Vec<&str> = fen.split_whitespace().collect();
+
+        if fields.len() != 6 {
+            return Err(FenError::FieldCount(fields.len()));
+        }
+
+        let squares = parse_piece_placement(fields[0])?;
+
+        let player = match fields[1] {
+            "w" => Player::WhitePlayer,
+            "b" => Player::BlackPlayer,
+            other => return Err(FenError::SideToMove(format!("expected \"w\" or \"b\" but found \"{}\"", other))),
+        };
+
+        let castling = parse_castling(fields[2], &squares)?;
+
+        let en_passant = parse_en_passant(fields[3], &squares, player)?;
+
+        let halfmove_clock: u32 = fields[4].parse()
+            .map_err(|_| FenError::HalfmoveClock(format!("\"{}\" is not a number", fields[4])))?;
+
+        let fullmove_number: u32 = match fields[5].parse() {
+            Ok(number) if number > 0 => number,
+            _ => return Err(FenError::FullmoveNumber(format!("\"{}\" is not a number above 0", fields[5]))),
+        };
+
+        let mut game = Game::new();
+
+        game.board = Board::from_squares(squares, castling, en_passant);
+        game.player = player;
+        game.halfmove_clock = halfmove_clock;
+        game.fullmove_number = fullmove_number;
+
+        let waiting_color = match player { //The player who just moved can never be left in check
+            Player::WhitePlayer => Color::Black,
+            Player::BlackPlayer => Color::White,
+        };
+
+        if game.is_in_check(waiting_color) {
+            return Err(FenError::SideToMove(format!("{:?} is to move but {:?} is in check", player, waiting_color)));
+        }
+
+        game.positions = vec![game.get_hash()];
+        game.start_fen = game.to_fen();
+        game.update_state();
+
+        Ok(game)
+    }
+
+    /// Writes the current position as FEN.
+    pub fn to_fen(&self) -> String {
+
+        let mut placement = String::new();
+
+        for row in (0..8).rev() { //FEN starts with the eighth rank
+
+            let mut empty = 0;
+
+            for column in 0..8 {
+
+                match &self.board.squares[row * 8 + column] {
+                    Some(piece) => {
+                        if empty > 0 {
+                            placement.push_str(&empty.to_string());
+                            empty = 0;
+                        }
+                        placement.push(piece_letter(piece));
+                    }
+                    None => empty += 1,
+                }
+            }
+
+            if empty > 0 {
+                placement.push_str(&empty.to_string());
+            }
+
+            if row > 0 {
+                placement.push('/');
+            }
+        }
+
+        let player = match self.player {
+            Player::WhitePlayer => "w",
+            Player::BlackPlayer => "b",
+        };
+
+        let rights = self.board.castling;
+        let mut castling: String = [
+            (rights.white_king_side, 'K'),
+            (rights.white_queen_side, 'Q'),
+            (rights.black_king_side, 'k'),
+            (rights.black_queen_side, 'q'),
+        ].iter().filter(|(allowed, _)| *allowed).map(|(_, letter)| letter).collect();
+
+        if castling.is_empty() {
+            castling.push('-');
+        }
+
+        let en_passant = match self.board.en_passant {
+            Some(square) => square.to_string(),
+            None => String::from("-"),
+        };
+
+        format!("{} {} {} {} {} {}", placement, player, castling, en_passant, self.halfmove_clock, self.fullmove_number)
+    }
+}
+
+/// The letter for a piece in FEN, upper case for white and lower case for black.
+pub(crate) fn piece_letter(piece: &Piece) -> char {
+
+    let letter = match piece.piece_type {
+        PieceType::Pawn => 'p',
+        PieceType::Knight => 'n',
+        PieceType::Bishop => 'b',
+        PieceType::Rook => 'r',
+        PieceType::Queen => 'q',
+        PieceType::King => 'k',
+    };
+
+    match piece.color {
+        Color::White => letter.to_ascii_uppercase(),
+        Color::Black => letter,
+    }
+}
+
+fn piece_from_letter(letter: char) -> Option<Piece> {
+
+    let piece_type = match letter.to_ascii_lowercase() {
+        'p' => PieceType::Pawn,
+        'n' => PieceType::Knight,
+        'b' => PieceType::Bishop,
+        'r' => PieceType::Rook,
+        'q' => PieceType::Queen,
+        'k' => PieceType::King,
+        _ => return None,
+    };
+
+    let color = if letter.is_ascii_uppercase() { Color::White } else { Color::Black };
+
+    Some(Piece::new(color, piece_type))
+}
+
+fn parse_piece_placement(field: &str) -> Result<[Option<Piece>; 64], FenError> {
+
+    let mut squares = [None; 64];
+
+    let ranks: Vec<&str> = field.split('/').collect();
+
+    if ranks.len() != 8 {
+        return Err(FenError::PiecePlacement(format!("expected 8 ranks but found {}", ranks.len())));
+    }
+
+    for (index, rank) in ranks.iter().enumerate() {
+
+        let row = 7 - index; //The first rank in the FEN is the eighth rank of the board
+        let mut column = 0;
+
+        for letter in rank.chars() {
+
+            if let Some(empty) = letter.to_digit(10).filter(|empty| (1..=8).contains(empty)) {
+                column += empty as usize;
+                continue;
+            }
+
+            let piece = piece_from_letter(letter)
+                .ok_or_else(|| FenError::PiecePlacement(format!("unknown piece '{}' on rank {}", letter, row + 1)))?;
+
+            if column >= 8 {
+                return Err(FenError::PiecePlacement(format!("rank {} has more than 8 squares", row + 1)));
+            }
+
+            if piece.piece_type == PieceType::Pawn && (row == 0 || row == 7) {
+                return Err(FenError::PiecePlacement(format!("pawn on rank {}", row + 1)));
+            }
+
+            squares[row * 8 + column] = Some(piece);
+            column += 1;
+        }
+
+        if column != 8 {
+            return Err(FenError::PiecePlacement(format!("rank {} has {} squares instead of 8", row + 1, column)));
+        }
+    }
+
+    for color in [Color::White, Color::Black] {
+
+        let kings = squares.iter().filter(|square| **square == Some(Piece::new(color, PieceType::King))).count();
+
+        if kings != 1 {
+            return Err(FenError::PiecePlacement(format!("{:?} has {} kings instead of 1", color, kings)));
+        }
+    }
+
+    Ok(squares)
+}
+
+fn parse_castling(field: &str, squares: &[Option<Piece>]) -> Result<CastlingRights, FenError> {
+
+    let mut rights = CastlingRights::none();
+
+    if field == "-" {
+        return Ok(rights);
+    }
+
+    for letter in field.chars() {
+
+        let (right, color, rook_square) = match letter { //Each right needs the king and the rook on their starting squares
+            'K' => (&mut rights.white_king_side, Color::White, Square::H1),
+            'Q' => (&mut rights.white_queen_side, Color::White, Square::A1),
+            'k' => (&mut rights.black_king_side, Color::Black, Square::H8),
+            'q' => (&mut rights.black_queen_side, Color::Black, Square::A8),
+            _ => return Err(FenError::Castling(format!("unknown castling right '{}'", letter))),
+        };
+
+        if *right {
+            return Err(FenError::Castling(format!("'{}' is given twice", letter)));
+        }
+
+        let king_square = match color {
+            Color::White => Square::E1,
+            Color::Black => Square::E8,
+        };
+
+        if squares[king_square.index()] != Some(Piece::new(color, PieceType::King)) || squares[rook_square.index()] != Some(Piece::new(color, PieceType::Rook)) {
+            return Err(FenError::Castling(format!("'{}' needs the king and rook on their starting squares", letter)));
+        }
+
+        *right = true;
+    }
+
+    Ok(rights)
+}
+
+fn parse_en_passant(field: &str, squares: &[Option<Piece>], player: Player) -> Result<Option<Square>, FenError> {
+
+    if field == "-" {
+        return Ok(None);
+    }
+
+    let square: Square = field.parse()
+        .map_err(|_| FenError::EnPassant(format!("\"{}\" is not a square", field)))?;
+
+    let (expected_rank, pawn_rank, pawn_color) = match player { //The pawn that skipped the square belongs to the player who just moved and stands right in front of it
+        Player::WhitePlayer => (Rank::Sixth, Rank::Fifth, Color::Black),
+        Player::BlackPlayer => (Rank::Third, Rank::Fourth, Color::White),
+    };
+
+    if square.rank() != expected_rank {
+        return Err(FenError::EnPassant(format!("{} is not on rank {}", field, expected_rank)));
+    }
+
+    if squares[square.index()].is_some() || squares[Square::from_file_rank(square.file(), pawn_rank).index()] != Some(Piece::new(pawn_color, PieceType::Pawn)) {
+        return Err(FenError::EnPassant(format!("there is no pawn that just skipped {}", field)));
+    }
+
+    Ok(Some(square))
+}
+
+
+
+
+// --------------------------
+// ######### TESTS ##########
+// --------------------------
+
+#[cfg(test)]
+mod tests {
+    use crate::{Color, DecisiveReason, Game, GameOutcome, GameState, PieceType, Player, Square};
+
+    use super::FenError;
+
+    const START: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+    #[test]
+    fn start_position_round_trip() {
+
+        assert_eq!(Game::new().to_fen(), START);
+        assert_eq!(Game::from_fen(START).unwrap().to_fen(), START);
+    }
+
+    #[test]
+    fn fen_follows_the_game() {
+
+        let mut game = Game::new();
+
+        game.play(Square::E2, Square::E4).unwrap(); // e4
+        assert_eq!(game.to_fen(), "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1");
+
+        game.play(Square::C7, Square::C5).unwrap(); // c5
+        game.play(Square::G1, Square::F3).unwrap(); // Nf3
+        assert_eq!(game.to_fen(), "rnbqkbnr/pp1ppppp/8/2p5/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2");
+    }
+
+    #[test]
+    fn all_fields_are_loaded() {
+
+        let game = Game::from_fen("r3k2r/8/8/3pP3/8/8/8/R3K2R w Kq d6 7 42").unwrap();
+
+        assert_eq!(game.whose_turn(), Player::WhitePlayer);
+        assert_eq!(game.get_board().get_en_passant(), Some(Square::D6));
+        assert_eq!(game.get_halfmove_clock(), 7);
+        assert_eq!(game.get_fullmove_number(), 42);
+
+        let rights = game.get_board().get_castling_rights();
+        assert!(rights.white_king_side && !rights.white_queen_side && !rights.black_king_side && rights.black_queen_side);
+
+        let king = game.get_board().get_squares()[4].unwrap();
+        assert_eq!((king.get_color(), king.get_piece_type()), (Color::White, PieceType::King));
+
+        assert!(game.validate_move(Square::E5, Square::D6).is_ok()); // exd6 en passant
+        assert!(game.validate_move(Square::E1, Square::G1).is_ok()); // O-O
+        assert!(game.validate_move(Square::E1, Square::C1).is_err());
+    }
+
+    #[test]
+    fn loaded_position_has_its_state() {
+
+        let mate = Game::from_fen("rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3").unwrap();
+
+        assert_eq!(mate.get_game_state(), GameState::GameOver);
+        assert_eq!(mate.get_outcome(), Some(GameOutcome::Decisive { winner: Player::BlackPlayer, reason: DecisiveReason::Checkmate }));
+    }
+
+    #[test]
+    fn errors_point_at_the_field() {
+
+        assert_eq!(Game::from_fen("8/8/8/8 w - - 0 1").unwrap_err(), FenError::PiecePlacement(String::from("expected 8 ranks but found 4")));
+        assert_eq!(Game::from_fen(START.replace(" 0 1", "").as_str()).unwrap_err(), FenError::FieldCount(4));
+        assert!(matches!(Game::from_fen("rnbqkbnr/pppppppp/9/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"), Err(FenError::PiecePlacement(_))));
+        assert!(matches!(Game::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNX w KQkq - 0 1"), Err(FenError::PiecePlacement(_))));
+        assert!(matches!(Game::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQ1BNR w kq - 0 1"), Err(FenError::PiecePlacement(_))));
+        assert!(matches!(Game::from_fen("rnbqkbnP/pppppppp/8/8/8/8/PPPPPPP1/RNBQKBNR w Qkq - 0 1"), Err(FenError::PiecePlacement(_))));
+        assert!(matches!(Game::from_fen(START.replace(" w ", " x ").as_str()), Err(FenError::SideToMove(_))));
+        assert!(matches!(Game::from_fen(START.replace("KQkq", "KQkx").as_str()), Err(FenError::Castling(_))));
+        assert!(matches!(Game::from_fen(START.replace("KQkq", "KKkq").as_str()), Err(FenError::Castling(_))));
+        assert!(matches!(Game::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBN1 w KQkq - 0 1"), Err(FenError::Castling(_))));
+        assert!(matches!(Game::from_fen(START.replace(" - ", " e3 ").as_str()), Err(FenError::EnPassant(_))));
+        assert!(matches!(Game::from_fen(START.replace(" - ", " e6 ").as_str()), Err(FenError::EnPassant(_))));
+        assert!(matches!(Game::from_fen(START.replace(" 0 1", " x 1").as_str()), Err(FenError::HalfmoveClock(_))));
+        assert!(matches!(Game::from_fen(START.replace(" 0 1", " 0 0").as_str()), Err(FenError::FullmoveNumber(_))));
+    }
+
+    #[test]
+    fn side_not_to_move_cannot_be_in_check() {
+
+        let error = Game::from_fen("4k3/4R3/8/8/8/8/8/4K3 w - - 0 1").unwrap_err();
+
+        assert!(matches!(error, FenError::SideToMove(_)));
+        assert_eq!(error.to_string(), "invalid side to move: WhitePlayer is to move but Black is in check");
+    }
+}
@@ -2,6 +2,30 @@ use std::fmt;
 use std::io;
 use std::io::prelude::*;
 
+mod attacks;
+pub mod bitboard;
+pub mod eval;
+mod exchange;
+pub mod fen;
+mod magic;
+pub mod ordering;
+pub mod perft;
+pub mod pgn;
+pub mod san;
+pub mod search;
+pub mod square;
+pub mod transposition;
+pub mod uci;
+mod zobrist;
+
+pub use bitboard::Bitboard;
+pub use eval::EvalConfigError;
+pub use fen::FenError;
+pub use pgn::{PgnError, PgnGame, PgnReader, Variation};
+pub use san::SanError;
+pub use square::{File, Rank, Square, SquareError};
+pub use uci::UciError;
+
 
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum GameState {
@@ -10,20 +34,64 @@ pub enum GameState {
     GameOver
 }
 
+/// How a finished game ended, see [`Game::get_outcome`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum GameOutcome {
+    /// One of the players won the game.
+    Decisive { winner: Player, reason: DecisiveReason },
+    /// Neither player won the game.
+    Draw { reason: DrawReason },
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum DecisiveReason {
+    /// The losing player is in check and has no legal moves.
+    Checkmate,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum DrawReason {
+    /// The player to move is not in check but has no legal moves.
+    Stalemate,
+    /// Neither player has enough pieces left to checkmate, so the game is drawn at once.
+    InsufficientMaterial,
+    /// Fifty moves by each player without a capture or pawn move, claimed by a player.
+    FiftyMoveRule,
+    /// Seventy-five moves by each player without a capture or pawn move, the game is drawn at once.
+    SeventyFiveMoveRule,
+    /// The same position occurred three times, claimed by a player.
+    ThreefoldRepetition,
+    /// The same position occurred five times, the game is drawn at once.
+    FivefoldRepetition,
+}
+
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum Player{
     WhitePlayer,
     BlackPlayer,
 }
 
-#[derive(Clone, PartialEq, Copy)]
+#[derive(Clone, PartialEq, Copy, Debug)]
 pub enum Color{
     White,
     Black,
 }
 
 
-#[derive( Clone, PartialEq, Copy)]
+impl Color {
+
+    /// The color of the other player.
+    pub fn opponent(self) -> Color {
+
+        match self {
+            Color::White => Color::Black,
+            Color::Black => Color::White,
+        }
+    }
+}
+
+
+#[derive( Clone, PartialEq, Copy, Debug)]
 pub enum PieceType{
     Pawn,
     Rook,
@@ -33,8 +101,55 @@ pub enum PieceType{
     King,
 }
 
+impl PieceType {
+
+    /// The pieces a pawn can be promoted to, the queen first.
+    pub const PROMOTIONS: [PieceType; 4] = [PieceType::Queen, PieceType::Rook, PieceType::Bishop, PieceType::Knight];
+}
+
+
+/// The reasons a move can be refused by the move API of [`Game`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum MoveError {
+    /// There is no piece on the square the move starts from.
+    EmptySquare(Square),
+    /// The piece on the starting square belongs to the opponent.
+    OpponentsPiece(Square),
+    /// The move was made on behalf of the player who is not to move.
+    NotYourTurn(Player),
+    /// The piece cannot reach the destination square.
+    IllegalDestination(Square),
+    /// The move would leave the players own king in check.
+    LeavesKingInCheck,
+    /// A pawn reaching the last rank has no piece to be promoted to.
+    MissingPromotion,
+    /// The promotion piece is not a queen, rook, bishop or knight, or the move is not a promotion.
+    InvalidPromotion,
+    /// The game has already ended so no more moves can be made.
+    GameOver,
+}
+
+impl fmt::Display for MoveError {
+
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+
+        match self {
+            MoveError::EmptySquare(square) => write!(f, "there is no piece at {}", square),
+            MoveError::OpponentsPiece(square) => write!(f, "the piece at {} belongs to the opponent", square),
+            MoveError::NotYourTurn(player) => write!(f, "it is not {:?}'s turn", player),
+            MoveError::IllegalDestination(square) => write!(f, "the piece cannot move to {}", square),
+            MoveError::LeavesKingInCheck => write!(f, "the move would leave the king in check"),
+            MoveError::MissingPromotion => write!(f, "no piece was chosen for the pawn to be promoted to"),
+            MoveError::InvalidPromotion => write!(f, "a pawn can only be promoted to a queen, rook, bishop or knight when it reaches the last rank"),
+            MoveError::GameOver => write!(f, "the game is already over"),
+        }
+    }
+}
+
+impl std::error::Error for MoveError {}
+
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Piece{
     color: Color,
     piece_type: PieceType,
@@ -44,13 +159,11 @@ pub struct Piece{
 impl Piece {
 
     pub fn new(color: Color, piece_type: PieceType) -> Piece {
-        
-        let piece = Piece {
+
+        Piece {
             color,
             piece_type,
-        };
-        
-        piece
+        }
     }
 
     pub fn get_color(&self) -> Color{
@@ -64,1058 +177,1311 @@ impl Piece {
 }
 
 
-#[derive(Clone)]
-pub struct Board {
-    
-    squares: Vec<Option<Piece>>,
+/// A move of the piece on `from` to `to`. A pawn reaching the last rank is promoted to `promotion`,
+/// or to the piece chosen with [`Game::set_promotion`] if no piece is given.
+///
+/// The moves given by [`Game`], like [`Game::legal_moves`] and [`Game::get_moves`], also tell which piece
+/// moved, what it captured and what kind of move it is. Moves made with [`Move::new`] leave these empty
+/// and are filled in when they are played. Two moves are equal if they have the same squares and promotion.
+#[derive(Copy, Clone, Debug)]
+pub struct Move {
+    pub from: Square,
+    pub to: Square,
+    pub promotion: Option<PieceType>,
+    /// The piece that moves, which is a pawn for promotions.
+    pub piece: Option<Piece>,
+    /// The piece that is captured, which is not on `to` when capturing en passant.
+    pub captured: Option<Piece>,
+    pub flags: MoveFlags,
 }
 
-impl Board { //Implements the board struct
+/// The special kinds of moves, see [`Move`].
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct MoveFlags {
+    /// The king moves two squares and the rook jumps over it.
+    pub castle: bool,
+    /// A pawn captures a pawn that just skipped the square it moves to.
+    pub en_passant: bool,
+    /// A pawn moves two squares from its starting rank.
+    pub double_push: bool,
+    /// The move puts the opponents king in check.
+    pub check: bool,
+}
 
-    pub fn new() -> Self { //This initializes a new board with all the pieces in their correct spots
-        let mut squares = Vec::with_capacity(64); // Create a vector with a capacity of 64 squares
-        // Initialize the vector with None values for each square
-        for _ in 0..64 {
-            squares.push(None);
-        }
-        //Puts all the pieces in their coorect starting spots
-        squares[0] = Some(Piece::new(Color::White, PieceType::Rook));
-        squares[1] = Some(Piece::new(Color::White, PieceType::Knight));
-        squares[2] = Some(Piece::new(Color::White, PieceType::Bishop));
-        squares[3] = Some(Piece::new(Color::White, PieceType::Queen));
-        squares[4] = Some(Piece::new(Color::White, PieceType::King));
-        squares[5] = Some(Piece::new(Color::White, PieceType::Bishop));
-        squares[6] = Some(Piece::new(Color::White, PieceType::Knight));
-        squares[7] = Some(Piece::new(Color::White, PieceType::Rook));
+impl Move {
 
-        for i in 8..16{
-            squares[i] = Some(Piece::new(Color::White, PieceType::Pawn));
+    pub fn new(from: Square, to: Square) -> Move {
 
+        Move {
+            from,
+            to,
+            promotion: None,
+            piece: None,
+            captured: None,
+            flags: MoveFlags::default(),
         }
-        squares[56] = Some(Piece::new(Color::Black, PieceType::Rook));
-        squares[57] = Some(Piece::new(Color::Black, PieceType::Knight));
-        squares[58] = Some(Piece::new(Color::Black, PieceType::Bishop));
-        squares[59] = Some(Piece::new(Color::Black, PieceType::Queen));
-        squares[60] = Some(Piece::new(Color::Black, PieceType::King));
-        squares[61] = Some(Piece::new(Color::Black, PieceType::Bishop));
-        squares[62] = Some(Piece::new(Color::Black, PieceType::Knight));
-        squares[63] = Some(Piece::new(Color::Black, PieceType::Rook));
+    }
 
-        for i in 48..56{
-            squares[i] = Some(Piece::new(Color::Black, PieceType::Pawn));
-            
+    pub fn with_promotion(from: Square, to: Square, piece_type: PieceType) -> Move {
+
+        Move {
+            promotion: Some(piece_type),
+            ..Move::new(from, to)
         }
-        Board { squares }
     }
 
-    pub fn get_squares(&self) -> &Vec<Option<Piece>> {
-        &self.squares
+    /// Returns `true` if the move captures a piece, including en passant.
+    pub fn is_capture(&self) -> bool {
+        self.captured.is_some()
     }
-
 }
 
+impl PartialEq for Move {
 
-#[derive(Clone)]
-pub struct Game {
-    
-    player: Player,
-    state: GameState,
-    board: Board,
-    
+    fn eq(&self, other: &Move) -> bool { //The rest of a move follows from the position it is played in
+
+        self.from == other.from && self.to == other.to && self.promotion == other.promotion
+    }
 }
 
 
-impl Game {
-    /// Initialises a new board. Sets the game state to in progress and player to white player.
-    pub fn new() -> Game {
+/// A move played in a [`Game`], with what the position looked like before it so that it can be taken back
+/// with [`Game::undo_move`]. The piece the move captured is kept in the move itself.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct HistoryEntry {
+    pub mv: Move,
+    /// The castling rights before the move.
+    pub castling: CastlingRights,
+    /// The en passant square before the move.
+    pub en_passant: Option<Square>,
+    /// The halfmove clock before the move.
+    pub halfmove_clock: u32,
+}
 
-        let initial_board = Board::new();
 
+/// Which castling moves each player is still allowed to make. A right is lost for good when the
+/// king or the rook on that side moves, or when the rook is captured.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CastlingRights {
+    pub white_king_side: bool,
+    pub white_queen_side: bool,
+    pub black_king_side: bool,
+    pub black_queen_side: bool,
+}
 
-        let game = Game {
+impl CastlingRights {
 
-            player: Player::WhitePlayer,
-            state: GameState::InProgress,
-            board: initial_board,
-            
-        };
+    /// Both players may castle to both sides, as in the starting position.
+    pub fn all() -> CastlingRights {
 
-        game
+        CastlingRights {
+            white_king_side: true,
+            white_queen_side: true,
+            black_king_side: true,
+            black_queen_side: true,
+        }
+    }
+
+    /// Neither player may castle.
+    pub fn none() -> CastlingRights {
 
+        CastlingRights {
+            white_king_side: false,
+            white_queen_side: false,
+            black_king_side: false,
+            black_queen_side: false,
+        }
     }
-    
-    pub fn get_game(self) -> (Game) {
 
-        return self;
+    fn king_side(&self, color: Color) -> bool {
+
+        match color {
+            Color::White => self.white_king_side,
+            Color::Black => self.black_king_side,
+        }
     }
 
-    pub fn get_board(&self) -> &Board {
-        &self.board
+    fn queen_side(&self, color: Color) -> bool {
+
+        match color {
+            Color::White => self.white_queen_side,
+            Color::Black => self.black_queen_side,
+        }
     }
 
+    fn remove(&mut self, color: Color) { //Used when the king moves, it can never castle again
 
-    pub fn from(&mut self) -> u32 { //This is a function that will let the user choose which piece to move by entering the coordinates for the square the piece is on
+        match color {
+            Color::White => {
+                self.white_king_side = false;
+                self.white_queen_side = false;
+            }
+            Color::Black => {
+                self.black_king_side = false;
+                self.black_queen_side = false;
+            }
+        }
+    }
 
-        println!("Which piece do you want to move?");
+    fn remove_for_square(&mut self, square: Square) { //Used when something moves from or to a corner, which means the rook there either moved or was captured
 
-        let mut place = 0;
+        match square {
+            Square::A1 => self.white_queen_side = false,
+            Square::H1 => self.white_king_side = false,
+            Square::A8 => self.black_queen_side = false,
+            Square::H8 => self.black_king_side = false,
+            _ => (),
+        }
+    }
+}
 
-        loop{
 
-            let (row, column) = convert_input_to_row_column();
+/// The pieces on the board, with the castling rights and the en passant square that belong to the position.
+///
+/// The pieces are kept twice: a piece for every square, to look up what stands where, and a [`Bitboard`]
+/// of the squares of each piece type and color, to find the pieces and their attacks quickly.
+#[derive(Clone)]
+pub struct Board {
 
-            place = ((row)*8)+column;
-            
+    squares: [Option<Piece>; 64],
+    pieces: [Bitboard; 6], //The squares of each piece type, in the order of PieceType
+    colors: [Bitboard; 2], //The squares of the white and of the black pieces
+    castling: CastlingRights,
+    en_passant: Option<Square>,
+    key: u64, //The Zobrist key of the pieces and castling rights, kept up to date with every change to them
+}
 
-            match &self.board.squares[place as usize] { //We check if there is a piece at the given square and weather it belongs to the user. 
+impl Board { //Implements the board struct
 
-                Some(_Piece) => {
+    pub fn new() -> Self { //This initializes a new board with all the pieces in their correct spots
+        let mut squares = [None; 64]; // Create an array with None values for each of the 64 squares
 
-                    let player_color = match self.player{ //First we note the players color
+        //Puts all the pieces in their coorect starting spots
+        squares[0] = Some(Piece::new(Color::White, PieceType::Rook));
+        squares[1] = Some(Piece::new(Color::White, PieceType::Knight));
+        squares[2] = Some(Piece::new(Color::White, PieceType::Bishop));
+        squares[3] = Some(Piece::new(Color::White, PieceType::Queen));
+        squares[4] = Some(Piece::new(Color::White, PieceType::King));
+        squares[5] = Some(Piece::new(Color::White, PieceType::Bishop));
+        squares[6] = Some(Piece::new(Color::White, PieceType::Knight));
+        squares[7] = Some(Piece::new(Color::White, PieceType::Rook));
 
-                        Player::WhitePlayer => Color::White,
-                        Player::BlackPlayer => Color::Black,
-                    };
-        
-                    match &self.board.squares[place as usize] { //If the color of the piece matches the color of the player the piece belongs to them
-
-                        Some(Piece) => {
-        
-                            if player_color == Piece.color{
-                                println!("This is your piece!");
-                                break;
-                            }
-                            else{
-                                println!("This square contains your opponents piece. Try selecting another square!");
-                            }
-                        },
-                        None => {
-                            println!("There is no piece at this square! Try selecting another square!");
-                        }
-                    }
-                }
+        for square in squares.iter_mut().take(16).skip(8) {
+            *square = Some(Piece::new(Color::White, PieceType::Pawn));
 
-                None => {
-                    println!("There is no piece at this square! Select another one!");
-                }
-            }
         }
+        squares[56] = Some(Piece::new(Color::Black, PieceType::Rook));
+        squares[57] = Some(Piece::new(Color::Black, PieceType::Knight));
+        squares[58] = Some(Piece::new(Color::Black, PieceType::Bishop));
+        squares[59] = Some(Piece::new(Color::Black, PieceType::Queen));
+        squares[60] = Some(Piece::new(Color::Black, PieceType::King));
+        squares[61] = Some(Piece::new(Color::Black, PieceType::Bishop));
+        squares[62] = Some(Piece::new(Color::Black, PieceType::Knight));
+        squares[63] = Some(Piece::new(Color::Black, PieceType::Rook));
 
-        place //Returns the square number for the piece the user wants to move.
+        for square in squares.iter_mut().take(56).skip(48) {
+            *square = Some(Piece::new(Color::Black, PieceType::Pawn));
 
+        }
+
+        Board::from_squares(squares, CastlingRights::all(), None)
     }
 
+    fn from_squares(squares: [Option<Piece>; 64], castling: CastlingRights, en_passant: Option<Square>) -> Board { //Sets up the bitboards for the pieces on the squares
+
+        let mut board = Board {
+            squares: [None; 64],
+            pieces: [Bitboard::EMPTY; 6],
+            colors: [Bitboard::EMPTY; 2],
+            castling,
+            en_passant,
+            key: zobrist::castling_key(castling),
+        };
 
-    pub fn to(&mut self, from: u32) -> u32 { //This function will let the user pick from the available moves for the piece they want to move. If there are no moves they have to pick again
+        for square in Square::all() {
+            board.put(square, squares[square.index()]);
+        }
 
-        let mut to: u32 = 0;
+        board
+    }
 
+    /// The piece on every square, from a1 to h8 rank by rank, see [`Square::index`].
+    pub fn get_squares(&self) -> &[Option<Piece>] {
+        &self.squares
+    }
 
-        let white = match self.player {
+    /// The piece standing on the square, if there is one.
+    pub fn get_piece(&self, square: Square) -> Option<Piece> {
+        self.squares[square.index()]
+    }
 
-            Player::WhitePlayer => true,
-            Player::BlackPlayer => false,
-        };
+    /// The squares of the pieces of the given color and type.
+    pub fn get_pieces(&self, color: Color, piece_type: PieceType) -> Bitboard {
+        self.colors[color as usize] & self.pieces[piece_type as usize]
+    }
 
-        let possible_moves: Vec<(u32, u32)> = Game::get_possible_moves(&self, from, white); //This gives us all the possible moves for the piece the player is trying to move
+    /// The squares of all the pieces of the given color.
+    pub fn get_color_pieces(&self, color: Color) -> Bitboard {
+        self.colors[color as usize]
+    }
 
-        let mut possible_moves_after_check = Vec::new();
+    /// The squares with a piece on them, of either color.
+    pub fn get_occupied(&self) -> Bitboard {
+        self.colors[0] | self.colors[1]
+    }
 
-        for &(row, column) in &possible_moves { //This function will pass all the moves to the legal_move function to see if the moves are legal or not.
+    pub fn get_castling_rights(&self) -> CastlingRights {
+        self.castling
+    }
 
-            let (from_row, from_column) = square_to_row_column(from);
+    /// The square a pawn skipped over with a two step move in the last move, where it can be captured en passant.
+    pub fn get_en_passant(&self) -> Option<Square> {
+        self.en_passant
+    }
 
-            let legal_move = self.legal_move((row, column), (from_row, from_column));
-            
-            if legal_move == true { //If the move is legal we can add it to the list
+    fn put(&mut self, square: Square, piece: Option<Piece>) { //Every change to the pieces goes through here so the squares and the bitboards always agree
 
-                possible_moves_after_check.push((row, column));
+        let bit = Bitboard::from(square);
 
-            }
+        if let Some(old) = self.squares[square.index()] {
+            self.pieces[old.piece_type as usize] &= !bit;
+            self.colors[old.color as usize] &= !bit;
+            self.key ^= zobrist::piece_key(old, square);
         }
 
-        if possible_moves_after_check.len() == 0 { //If there are no legal moves the player has to choose again
+        if let Some(new) = piece {
+            self.pieces[new.piece_type as usize] |= bit;
+            self.colors[new.color as usize] |= bit;
+            self.key ^= zobrist::piece_key(new, square);
+        }
 
-            println!("There are no possible moves for this piece. Choose another one");
+        self.squares[square.index()] = piece;
+    }
 
-            Game::from(self);
+    fn set_castling(&mut self, castling: CastlingRights) {
 
-        }
-        else {
+        self.key ^= zobrist::castling_key(self.castling) ^ zobrist::castling_key(castling);
+        self.castling = castling;
+    }
 
-            loop {
-                
-                println!("These are the possible moves:");
+    fn take(&mut self, square: Square) -> Option<Piece> { //Removes the piece from the square and returns it
 
-                for &(row, column) in &possible_moves_after_check {
+        let piece = self.squares[square.index()];
 
-                    let (letter, rank) = convert_row_column_to_output(row, column);
+        self.put(square, None);
 
-                    //Prints out all the possible moves from which the user can choose which one they want.
-                    print!("{}", letter);
-                    print!("{}", rank);
-                    println!(" ");
+        piece
+    }
 
-                }
+    fn king_square(&self, color: Color) -> Option<Square> {
 
-                println!("Choose one of these moves!");
+        self.get_pieces(color, PieceType::King).first()
+    }
 
-                let chosen_move: (u32, u32) = convert_input_to_row_column();
+    fn apply_move(&mut self, mv: Move, promotion: PieceType) { //Moves the pieces, a pawn reaching the last rank without a promotion in the move becomes the given piece
 
-                let mut move_in_list = false;
+        let Move { from, to, .. } = mv;
 
-                for (row1, column1) in &possible_moves_after_check { //First checks so the move the user picked is in the list of posible moves. If it is it will move the piece
+        let mut castling = self.castling;
 
-                    if chosen_move == (*row1, *column1) {
+        let mut swap = self.take(from); //We store the piece we are trying to move in swap and leave the source as empty
 
-                        move_in_list = true;
+        let en_passant = self.en_passant.take(); //The pawn can only be captured en passant right after its two step move
 
-                        let to = row_column_to_square(chosen_move);
+        if let Some(piece) = swap {
 
-                        Game::make_move(self, from, to);
+            if piece.piece_type == PieceType::Pawn {
 
-                    }
+                if from.rank().index().abs_diff(to.rank().index()) == 2 { //The square the pawn skipped can be captured on in the next move
+                    self.en_passant = Square::new((u32::from(from) + u32::from(to)) / 2);
                 }
-
-                if move_in_list == false { //If the move wasnt in the list of possible moves the user has to pick again
-
-                    println!("Choose one of the possible moves!");
+                else if Some(to) == en_passant && from.file() != to.file() { //Capturing en passant, the captured pawn is beside the pawn and not on the destination
+                    self.put(Square::from_file_rank(to.file(), from.rank()), None);
                 }
-                else{
-                    break;
+                else if to.rank() == Rank::First || to.rank() == Rank::Eighth { //The pawn reached the last rank and is replaced with the promotion piece
+                    swap = Some(Piece::new(piece.color, mv.promotion.unwrap_or(promotion)));
                 }
+            }
+
+            if piece.piece_type == PieceType::King {
+
+                castling.remove(piece.color); //Once the king has moved it can not castle anymore
 
-            
+                let rook_squares = match to.file().index() as i32 - from.file().index() as i32 {
+                    2 => from.offset(3, 0).zip(from.offset(1, 0)), //Castling king side, the rook jumps from the corner to the square the king passed
+                    -2 => from.offset(-4, 0).zip(from.offset(-1, 0)), //Castling queen side
+                    _ => None,
+                };
 
-            
+                if let Some((corner, passed)) = rook_squares {
+                    let rook = self.take(corner);
+                    self.put(passed, rook);
+                }
             }
-        
         }
 
-        to
-
-    }
+        castling.remove_for_square(from); //A rook leaving its corner or being captured there loses the right to castle with it
+        castling.remove_for_square(to);
 
-    /// If the current game state is `InProgress` and the move is legal, 
-    /// move a piece and return the resulting state of the game.
-    pub fn make_move(&mut self, from: u32, to: u32) { //This funciton will move the piece.
+        self.set_castling(castling);
 
-        let swap = std::mem::replace(&mut self.board.squares[from as usize], None); //We store the piece we are trying to move in swap and leave the source as empty
+        self.put(to, swap); //The destination square gets the piece of the source square. This will override anything at teh square so it also works well for capturing.
 
-        self.board.squares[to as usize] = swap; //The destination square gets the piece of the source square. This will override anything at teh square so it also works well for capturing.
-        
     }
 
-    /// (Optional but recommended) Set the piece type that a pawn becames following a promotion.
-    pub fn set_promotion(&mut self, _piece: &str) -> () {
-        ()
-    }
+}
 
-    /// Get the current game state.
-    pub fn get_game_state(&self) -> GameState { //Gets the state of the game
-        self.state
-    }
-    
-    /// If a piece is standing on the given tile, return all possible 
-    /// new positions of that piece. Don't forget to the rules for check. 
-    /// 
-    /// (optional) Implement en passant and castling.
-    pub fn get_possible_moves(&self, from: u32, white: bool) -> Vec<(u32, u32)> { //This function will first determine which type of piece we have and then call the specific function for getting move of that piecetype
-        
-        let player_color = match white { //Because we cannot use a private enum Color as parameter for a public function we instead have a boolean which tells us if the player is white or not. 
+impl Default for Board {
 
-            true => Color::White,
-            false => Color::Black,
-        };
+    fn default() -> Self {
+        Board::new()
+    }
+}
 
-        let mut possible_moves: Vec<(u32, u32)> = Vec::new();
 
-        //Make the from into a vector with the row and column of the piece
+#[derive(Clone)]
+pub struct Game {
 
-        let (row, column) = square_to_row_column(from);
+    player: Player,
+    state: GameState,
+    board: Board,
+    promotion: PieceType,
+    outcome: Option<GameOutcome>,
+    halfmove_clock: u32,
+    fullmove_number: u32,
+    positions: Vec<u64>, //The keys of the positions so far, to find repetitions
+    start_fen: String,
+    history: Vec<HistoryEntry>,
+    undone: Vec<Move>, //The moves taken back with undo_move, the last one is redone first
 
-        let from_row_column = (row, column);
+}
 
 
-        match &self.board.squares[from as usize] { //Determines the piece type on the square the piece the player is trying to move.
+impl Game {
+    /// Initialises a new board. Sets the game state to in progress and player to white player.
+    pub fn new() -> Game {
 
-            Some(piece) => {
+        let initial_board = Board::new();
 
-                let piece_type = match piece.piece_type {
-                    
-                    PieceType::King => PieceType::King,
-                    PieceType::Queen => PieceType::Queen,
-                    PieceType::Bishop => PieceType::Bishop,
-                    PieceType::Knight => PieceType::Knight,
-                    PieceType::Rook => PieceType::Rook,
-                    PieceType::Pawn => PieceType::Pawn,
 
-                    };
+        let mut game = Game {
 
-                    //Depending on which piece type it is differnt functions are called.
-                    if piece_type == PieceType::King {
+            player: Player::WhitePlayer,
+            state: GameState::InProgress,
+            board: initial_board,
+            promotion: PieceType::Queen,
+            outcome: None,
+            halfmove_clock: 0,
+            fullmove_number: 1,
+            positions: Vec::new(),
+            start_fen: String::new(),
+            history: Vec::new(),
+            undone: Vec::new(),
 
-                        possible_moves = self.possible_moves_king(from_row_column, player_color);
+        };
 
-                    }      
-                    else if piece_type == PieceType::Knight {
+        game.positions.push(game.get_hash());
+        game.start_fen = game.to_fen();
 
-                        possible_moves = self.possible_moves_knight(from_row_column, player_color);
+        game
 
-                    }
-                    else if piece_type == PieceType::Rook {
-                        
-                        possible_moves = self.possible_moves_rook(from_row_column, player_color);
-                        
-                    }
-                    else if piece_type == PieceType::Queen {
+    }
 
-                        possible_moves = self.possible_moves_queen(from_row_column, player_color);
+    pub fn get_game(self) -> Game {
 
-                    }
-                    else if piece_type == PieceType::Bishop {
-                        
-                        possible_moves = self.possible_moves_bishop(from_row_column, player_color);
-                       
-                    }
-                    else if piece_type == PieceType::Pawn {
+        self
+    }
 
-                        possible_moves = self.possible_moves_pawn(from_row_column, player_color);
-                        
-                    }
-                }        
-        
-            None => {
+    pub fn get_board(&self) -> &Board {
+        &self.board
+    }
 
-                println!("There is no piece at this square!");
-            }
 
-        }
+    pub fn from(&mut self) -> Square { //This is a function that will let the user choose which piece to move by entering the coordinates for the square the piece is on
 
-        possible_moves //Returns all the possible moves
+        println!("Which piece do you want to move?");
 
-    }
+        let player_color = self.player_color(); //First we note the players color
 
-    pub fn possible_moves_king(&self, from: (u32, u32), player_color: Color) -> Vec<(u32, u32)>{ //Determines the possible moves for the king
+        loop{
 
-            //The king can move in each direction one step. If it is not at the edge of the board this is eight possible squares.
-            //The kings move the same regardless of color
+            let place = match read_square() {
+                Some(place) => place,
+                None => {
+                    println!("Write a square like a3!");
+                    continue;
+                }
+            };
 
-        let mut possible_moves: Vec<(u32, u32)> = Vec::new();
+            match &self.board.squares[place.index()] { //We check if there is a piece at the given square and weather it belongs to the user.
 
-        let mut moves_on_the_board: Vec<(u32, u32)> = Vec::new();
+                Some(piece) if piece.color == player_color => { //If the color of the piece matches the color of the player the piece belongs to them
+                    println!("This is your piece!");
+                    return place; //Returns the square number for the piece the user wants to move.
+                }
+                Some(_) => {
+                    println!("This square contains your opponents piece. Try selecting another square!");
+                }
+                None => {
+                    println!("There is no piece at this square! Select another one!");
+                }
+            }
+        }
 
-        let moves = [ //The king always has 8 different moves it could make (not counting castling). These are the changes in row and column for every move
+    }
 
-            (1, -1), (1, 0), (1, 1),
 
-            (0, -1),         (0, 1),
+    pub fn to(&mut self, from: Square) -> Square { //This function will let the user pick from the available moves for the piece they want to move. If there are no moves they have to pick again
 
-            (-1, -1), (-1, 0), (-1, 1),
+        let possible_moves_after_check = self.legal_moves_from(from); //This gives us all the legal moves for the piece the player is trying to move
 
-        ];
-            
-        let (mut row, mut column) = from;
-        
-            
-        for (r, c) in moves.iter() { //First we check that the move doesnt take the piece outside of the board.
+        if possible_moves_after_check.is_empty() { //If there are no legal moves the player has to choose again
 
-            let (row1, column1) = ((row as i32) + *r, (column as i32) + *c);
+            println!("There are no possible moves for this piece. Choose another one");
 
-            if -1 < row1 && row1 < 8 {
+            let from = Game::from(self);
 
-                if -1 < column1 && column1 < 8 {
+            return Game::to(self, from);
 
-                    moves_on_the_board.push((row1 as u32, column1 as u32));
-                }
-            }
         }
 
-        for i in 0..moves_on_the_board.len() { //This will check so the piece cant stand on top of another piece of the same color, but it can still capture the opponents piece.
+        loop {
 
-            let (row, column) = moves_on_the_board.get(i).unwrap();
-            let square: u32 = ((row*8) + column).try_into().unwrap();
+            println!("These are the possible moves:");
 
-            match &self.board.squares[square as usize] { //Chekc if the square is empty or not
+            for mv in &possible_moves_after_check {
 
-                Some(_Piece) => {
+                let san = self.move_to_san(*mv).unwrap_or_default();
 
-                    match &self.board.squares[square as usize] { //If there is a piece we can still move there if the piece  color and player color do not match
-                        Some(Piece) => {
-                
-                            if player_color != Piece.color{
-                                    
-                                possible_moves.push((*row, *column));
+                //Prints out all the possible moves from which the user can choose which one they want, together with how the move is written in SAN.
+                println!("{} ({})", mv, san);
 
-                            }
+            }
 
-                        },
-                        None => { //If the square is empty we can move there
+            println!("Choose one of these moves! Writing only the square to move to is also fine.");
 
-                            possible_moves.push((*row, *column));
+            let input = read_input();
 
-                        }
+            //The user can write the whole move like "e7e8q" or just the square to move to, which promotes to the piece chosen with set_promotion
+            let chosen_move = match input.parse() {
+                Ok(to) => Move::new(from, to),
+                Err(_) => match Move::from_uci(&input) {
+                    Ok(mv) => mv,
+                    Err(error) => {
+                        println!("{}", error);
+                        continue;
                     }
-                }
+                },
+            };
 
-                None => {
-                    possible_moves.push((*row, *column));
+            if possible_moves_after_check.iter().any(|mv| mv.from == chosen_move.from && mv.to == chosen_move.to) { //First checks so the move the user picked is in the list of posible moves. If it is it will move the piece
+
+                match self.play_move(chosen_move) {
+                    Ok(_) => return chosen_move.to,
+                    Err(error) => println!("The move is not allowed: {}", error),
                 }
             }
+            else {
+                println!("Choose one of the possible moves!"); //If the move wasnt in the list of possible moves the user has to pick again
+            }
         }
 
-        possible_moves
-
     }
 
+    /// Plays a move for the player whose turn it is, without reading input or printing anything.
+    ///
+    /// The move is refused with a [`MoveError`] if it does not pass [`Game::validate_move`].
+    /// Otherwise the piece is moved, the turn passes to the opponent and the new game state is returned.
+    /// A pawn reaching the last rank becomes the piece chosen with [`Game::set_promotion`].
+    pub fn play(&mut self, from: Square, to: Square) -> Result<GameState, MoveError> {
+
+        self.play_move(Move::new(from, to))
+    }
 
-    pub fn possible_moves_knight(&self, from: (u32, u32), player_color: Color) -> Vec<(u32, u32)> { //Returns all the possible moves of the knight
+    /// Like [`Game::play`] but a promotion can be given with the move.
+    pub fn play_move(&mut self, mv: Move) -> Result<GameState, MoveError> {
 
-        let mut possible_moves: Vec<(u32, u32)> = Vec::new();
+        self.validate_move(mv.from, mv.to)?;
 
-        let mut moves_on_the_board: Vec<(u32, u32)> = Vec::new();
+        match mv.promotion {
+            None => {}
+            Some(piece_type) if self.is_promotion(mv.from, mv.to) && PieceType::PROMOTIONS.contains(&piece_type) => {}
+            Some(_) => return Err(MoveError::InvalidPromotion),
+        }
 
-        let moves: [(i32, i32); 8] = [ //Like the king. The knight also has pre determined moves it could make regardless of color
+        let promotion = match self.is_promotion(mv.from, mv.to) { //The move is recorded with the piece the pawn actually became
+            true => Some(mv.promotion.unwrap_or(self.promotion)),
+            false => None,
+        };
 
-            (2, -1), (2, 1), 
-            
-            (1, -2), (1, 2),
-                
-            (-1, -2), (-1, 2), 
-                
-            (-2, -1), (-2, 1),
+        let mut record = self.describe(mv.from, mv.to, promotion);
 
-        ];
+        let entry = HistoryEntry {
+            mv: record,
+            castling: self.board.castling,
+            en_passant: self.board.en_passant,
+            halfmove_clock: self.halfmove_clock,
+        };
 
-        //Except for the specific moves the piece can make this function is almost the exact same as the one for the king moves
-            
-        let (mut row, mut column) = from;
-        
-            
-        for (r, c) in moves.iter() {
+        self.apply_move(mv);
+        self.change_player();
 
-            let (row1, column1) = ((row as i32) + *r, (column as i32) + *c);
+        record.flags.check = self.is_in_check(self.player_color());
+        self.history.push(HistoryEntry { mv: record, ..entry });
+        self.undone.clear(); //A new move starts a new line, so the moves taken back can not be redone anymore
 
-            if -1 < row1 && row1 < 8 {
+        if self.player == Player::WhitePlayer { //A full move is over once black has moved
+            self.fullmove_number += 1;
+        }
 
-                if -1 < column1 && column1 < 8 {
+        let pawn_move = matches!(record.piece, Some(piece) if piece.piece_type == PieceType::Pawn);
 
-                    moves_on_the_board.push((row1 as u32, column1 as u32));
-                }
-            }
+        if pawn_move || record.is_capture() { //Captures and pawn moves can not be undone over the board, which starts the count for the fifty-move rule again
+            self.halfmove_clock = 0;
+        }
+        else {
+            self.halfmove_clock += 1;
         }
 
-        for i in 0..moves_on_the_board.len() {
+        self.positions.push(self.get_hash());
+        self.update_state();
 
-            let (row, column) = moves_on_the_board.get(i).unwrap();
-            let square: u32 = ((row*8) + column).try_into().unwrap();
+        Ok(self.state)
+    }
 
-            match &self.board.squares[square as usize] {
+    /// Takes back the last move played and returns it, or `None` if no moves have been played.
+    ///
+    /// The board, castling rights, en passant square and clocks go back to how they were before the move,
+    /// and a game that ended with the move is in progress again. The move can be played again with [`Game::redo_move`].
+    pub fn undo_move(&mut self) -> Option<Move> {
 
-                Some(_Piece) => {
+        let entry = self.history.pop()?;
+        let mv = entry.mv;
 
-                    match &self.board.squares[square as usize] {
-                        Some(Piece) => {
-            
-                            if player_color != Piece.color{
-                                    
-                                possible_moves.push((*row, *column));
+        let piece = mv.piece.expect("a played move always has the piece that moved");
 
-                            }
+        self.board.put(mv.from, Some(piece)); //The piece goes back, which also turns a promoted piece back into a pawn
+        self.board.put(mv.to, None);
 
-                        },
-                        None => {
+        let captured_square = match mv.flags.en_passant {
+            true => Square::from_file_rank(mv.to.file(), mv.from.rank()), //The pawn captured en passant stood beside the capturing pawn
+            false => mv.to,
+        };
 
-                            possible_moves.push((*row, *column));
+        self.board.put(captured_square, mv.captured);
 
-                        }
-                    }
-                }
+        if mv.flags.castle { //The rook jumps back to its corner
 
-                None => {
-                    possible_moves.push((*row, *column));
-                }
-            }
+            let (corner, passed) = match mv.to.file() {
+                File::G => (Square::from_file_rank(File::H, mv.from.rank()), Square::from_file_rank(File::F, mv.from.rank())),
+                _ => (Square::from_file_rank(File::A, mv.from.rank()), Square::from_file_rank(File::D, mv.from.rank())),
+            };
+
+            let rook = self.board.take(passed);
+            self.board.put(corner, rook);
         }
 
-        possible_moves
+        self.board.set_castling(entry.castling);
+        self.board.en_passant = entry.en_passant;
+        self.halfmove_clock = entry.halfmove_clock;
 
+        if self.player == Player::WhitePlayer { //Taking back a move by black goes back to the previous full move
+            self.fullmove_number -= 1;
+        }
+
+        self.change_player();
+        self.positions.pop();
+        self.undone.push(mv);
+        self.update_state();
+
+        Some(mv)
     }
-    
 
-    pub fn possible_moves_rook(&self, from: (u32, u32), player_color: Color) -> Vec<(u32, u32)> { //Returns all the possible moves of the rook
+    /// Plays the last move taken back with [`Game::undo_move`] again and returns it, or `None` if there is none.
+    /// Playing any other move forgets the moves that were taken back.
+    pub fn redo_move(&mut self) -> Option<Move> {
 
-        let mut possible_moves: Vec<(u32, u32)> = Vec::new();
+        let mv = self.undone.pop()?;
+        let undone = std::mem::take(&mut self.undone);
 
-        let directions: [(i32, i32); 4] = [
+        let result = self.play_move(mv);
 
-            (1, 0), (-1, 0), (0, 1), (0, -1), //These are the directions for the rook. Instead of specific move the rook can move in certain directions. These are the changes in row and column for one step in each direction
+        self.undone = undone; //Playing the move forgot the other moves taken back, but they can still be redone
 
-        ];
-            
-        let (row, column) = from;
-            
-        for (r, c) in directions.iter() { //We will see how far we can move in each direction. It will "move" the piece as far as possible in all four direction, one move at a time and check if the move is possible or not.
-
-            let (mut to_row, mut to_column) = ((row as i32) + *r, (column as i32) + *c);
-
-            while -1 < to_row && to_row < 8 && -1 < to_column && to_column < 8 {
-
-                let square = row_column_to_square((to_row as u32, to_column as u32));
-
-                
-                match &self.board.squares[square as usize] { //The rook can move in any direction until it reaches the border or another piece. This function sees how far it can move
-
-                    Some(Piece) => {
-                    
-                        if player_color == Piece.color{ //If we find the same players piece we cannot move anymore
-                            break;
-                        }
-                        else {          // If we find the opponents piece we can capture it mut not move beyond that.
-                            possible_moves.push((to_row as u32, to_column as u32));
-                            break;
-                        }
-                        
-        
-                        },
-                    None => { //If nothing is there we can move there
-        
-                        possible_moves.push((to_row as u32, to_column as u32));
-        
-                    }
-                }
+        match result {
+            Ok(_) => self.history.last().map(|entry| entry.mv),
+            Err(_) => {
+                self.undone.push(mv);
+                None
+            }
+        }
+    }
+
+    /// The moves played so far, with the state needed to take them back. See [`Game::get_moves`] for only the moves.
+    pub fn history(&self) -> &[HistoryEntry] {
+        &self.history
+    }
+
+    /// The number of half moves played since the start of the game, or since the position it was set up from.
+    pub fn ply(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Takes back or redoes moves until `ply` half moves have been played, see [`Game::ply`].
+    /// Returns `false` and leaves the game as it is if there are not that many moves to go to.
+    pub fn go_to_ply(&mut self, ply: usize) -> bool {
+
+        if ply > self.history.len() + self.undone.len() {
+            return false;
+        }
+
+        while self.history.len() > ply {
+            self.undo_move();
+        }
 
-                to_row = (to_row as i32) + *r; //After every move, if the square was empty we continue moving in the same direction. We do this until we reach another piece or the edge of the board.
-                to_column = (to_column as i32) + *c;
+        while self.history.len() < ply {
+            if self.redo_move().is_none() {
+                return false;
             }
         }
 
-        return possible_moves;
+        true
+    }
+
+    /// Like [`Game::play`] but the move is made on behalf of `player`, which is refused with
+    /// [`MoveError::NotYourTurn`] if it is the other players turn. Useful when the moves come from a server.
+    pub fn play_as(&mut self, player: Player, from: Square, to: Square) -> Result<GameState, MoveError> {
+
+        if self.state != GameState::GameOver && player != self.player {
+            return Err(MoveError::NotYourTurn(player));
+        }
 
+        self.play(from, to)
     }
 
-    pub fn possible_moves_bishop(&self, from: (u32, u32), player_color: Color) -> Vec<(u32, u32)> { //Returns all the possible moves for the bishop
+    /// Checks if the player whose turn it is may move the piece on `from` to `to`, without moving it.
+    ///
+    /// The move is refused if the game is over, there is no piece of the current player on `from`,
+    /// the piece cannot reach `to` or the move leaves the king in check.
+    pub fn validate_move(&self, from: Square, to: Square) -> Result<(), MoveError> {
 
-        let mut possible_moves: Vec<(u32, u32)> = Vec::new();
+        if self.state == GameState::GameOver {
+            return Err(MoveError::GameOver);
+        }
 
-        let directions: [(i32, i32); 4] = [
+        match &self.board.squares[from.index()] {
+            Some(piece) if piece.color == self.player_color() => {}
+            Some(_) => return Err(MoveError::OpponentsPiece(from)),
+            None => return Err(MoveError::EmptySquare(from)),
+        }
 
-            (1, 1), (1, -1), (-1, 1), (-1, -1), //Similarly to the rook, the bishop can move until another piece or the edge of the board blocks it in four directions. The only difference is that it moves idagonally.
+        let white = self.player == Player::WhitePlayer;
 
-        ];
-        
-        //Since the moving is so similar to the rook the rest of the function is basically the same as the rook
-        let (row, column) = from;        
-            
-        for (r, c) in directions.iter() {
-
-            let (mut to_row, mut to_column) = ((row as i32) + *r, (column as i32) + *c);
-
-            while -1 < to_row && to_row < 8 && -1 < to_column && to_column < 8 {
-
-                let square = row_column_to_square((to_row as u32, to_column as u32));
-                
-                match &self.board.squares[square as usize] {
-
-                    Some(Piece) => {
-                    
-                        if player_color == Piece.color{ //If we find the same players piece we cannot move anymore
-                            break;
-                        }
-                        else {          // If we find the opponents piece we can capture it mut not move beyond that.
-                            possible_moves.push((to_row as u32, to_column as u32));
-                            break;
-                        }
-                        
-        
-                        },
-                    None => { //If nothing is there we can move there
-        
-                        possible_moves.push((to_row as u32, to_column as u32));
-        
-                    }
-                }
+        if !self.get_possible_moves(from, white)?.iter().any(|mv| mv.to == to) {
+            return Err(MoveError::IllegalDestination(to));
+        }
 
-                to_row = (to_row as i32) + *r;
-                to_column = (to_column as i32) + *c;
-            }
+        if !self.legal_move(from, to) {
+            return Err(MoveError::LeavesKingInCheck);
         }
 
-        return possible_moves;
+        Ok(())
+    }
+
+    /// If the current game state is `InProgress` and the move is legal,
+    /// move a piece and return the resulting state of the game.
+    ///
+    /// A king moving two squares sideways is castling, so the rook is moved to the other side of the king as well.
+    /// A pawn moving diagonally to the en passant square captures the pawn that skipped over it.
+    pub fn make_move(&mut self, from: Square, to: Square) { //This funciton will move the piece.
+
+        self.apply_move(Move::new(from, to));
 
     }
-    
 
-    pub fn possible_moves_queen(&self, from: (u32, u32), player_color: Color) -> Vec<(u32, u32)> { //Returns all the possible moves for the queen
+    fn apply_move(&mut self, mv: Move) {
 
-        let mut possible_moves: Vec<(u32, u32)> = Vec::new();
+        self.board.apply_move(mv, self.promotion);
+    }
 
-        let directions: [(i32, i32); 8] = [
+    /// (Optional but recommended) Set the piece type that a pawn becames following a promotion.
+    ///
+    /// Accepts "queen", "rook", "bishop" or "knight", or their first letter ("n" for knight). The default is a queen.
+    pub fn set_promotion(&mut self, piece: &str) -> Result<(), MoveError> {
+
+        self.promotion = match piece.to_lowercase().as_str() {
+            "queen" | "q" => PieceType::Queen,
+            "rook" | "r" => PieceType::Rook,
+            "bishop" | "b" => PieceType::Bishop,
+            "knight" | "n" => PieceType::Knight,
+            _ => return Err(MoveError::InvalidPromotion),
+        };
 
-            (1, 0), (-1, 0), (0, 1), (0, -1), (1,1), (1,-1), (-1,1), (-1,-1), //The queen has all the combined directions of the rook and the bishop
+        Ok(())
+    }
 
-        ];
-        
-        //This function is also the same as the last two, just the directions are different.
-        let (row, column) = from;
-            
-        for (r, c) in directions.iter() {
-
-            let (mut to_row, mut to_column) = ((row as i32) + *r, (column as i32) + *c);
-
-            while -1 < to_row && to_row < 8 && -1 < to_column && to_column < 8 {
-
-                let square = row_column_to_square((to_row as u32, to_column as u32));
-
-                match &self.board.squares[square as usize] {
-
-                    Some(Piece) => {
-                    
-                        if player_color == Piece.color{ //If we find the same players piece we cannot move anymore
-                            break;
-                        }
-                        else {          // If we find the opponents piece we can capture it mut not move beyond that.
-                            possible_moves.push((to_row as u32, to_column as u32));
-                            break;
-                        }
-                        
-        
-                        },
-                    None => { //If nothing is there we can move there
-        
-                        possible_moves.push((to_row as u32, to_column as u32));
-        
-                    }
-                }
+    /// Returns `true` if the move takes a pawn to the last rank, where it has to be promoted.
+    pub fn is_promotion(&self, from: Square, to: Square) -> bool {
 
-                to_row = (to_row as i32) + *r;
-                to_column = (to_column as i32) + *c;
-            }
+        match &self.board.squares[from.index()] {
+            Some(piece) if piece.piece_type == PieceType::Pawn => match piece.color {
+                Color::White => to.rank() == Rank::Eighth,
+                Color::Black => to.rank() == Rank::First,
+            },
+            _ => false,
         }
+    }
 
-        return possible_moves;
-
+    /// Get the current game state.
+    pub fn get_game_state(&self) -> GameState { //Gets the state of the game
+        self.state
     }
 
+    /// If a piece is standing on the given tile, return all possible
+    /// moves of that piece, without looking at whether they leave the king in check.
+    /// A pawn reaching the last rank gives one move for each piece it can be promoted to.
+    pub fn get_possible_moves(&self, from: Square, white: bool) -> Result<Vec<Move>, MoveError> { //This function will first determine which type of piece we have and then call the specific function for getting move of that piecetype
 
-    pub fn possible_moves_pawn(&self, from: (u32, u32), player_color: Color) -> Vec<(u32, u32)> { //Returns all the possible moves of the pawn
+        let player_color = match white { //Because we cannot use a private enum Color as parameter for a public function we instead have a boolean which tells us if the player is white or not.
 
-        //The pawn is difficult since it has different directions depending on the player since pawns can only move relatively forward.
-        let mut possible_moves: Vec<(u32, u32)> = Vec::new();
+            true => Color::White,
+            false => Color::Black,
+        };
 
-        let mut moves: [(i32, i32); 4] = [(0,0), (0,0), (0,0),(0,0),]; 
+        match &self.board.squares[from.index()] { //Determines the piece type on the square the piece the player is trying to move.
 
-        if player_color == Color::Black { //Depending on the player, the pawn has different moves.
+            //Depending on which piece type it is differnt functions are called.
+            Some(piece) => Ok(match piece.piece_type {
 
-            moves = [
+                PieceType::King => self.possible_moves_king(from, player_color),
+                PieceType::Knight => self.possible_moves_knight(from, player_color),
+                PieceType::Rook => self.possible_moves_rook(from, player_color),
+                PieceType::Queen => self.possible_moves_queen(from, player_color),
+                PieceType::Bishop => self.possible_moves_bishop(from, player_color),
+                PieceType::Pawn => self.possible_moves_pawn(from, player_color),
+            }),
+
+            None => Err(MoveError::EmptySquare(from)),
 
-                (-1,1), (-1,0), (-1,-1), (-2, 0), //There are five possible moves at most for a pawn. Forward one step, forward two steps if it hasnt moved before, and to each side if a piece of the opposing color is occupying that square
-            ]
         }
-        else {
 
-            moves = [
+    }
 
-                (1,1), (1,0), (1,-1), (2,0),
-            ]
-        }
-          
-        let (mut row, mut column) = from;
-        
-            
-        for (r, c) in moves.iter() { //Determines the possible moves for the pawn
+    pub fn possible_moves_king(&self, from: Square, player_color: Color) -> Vec<Move>{ //Determines the possible moves for the king
+
+            //The king can move in each direction one step. If it is not at the edge of the board this is eight possible squares.
+            //The kings move the same regardless of color
 
-            let (row1, column1) = ((row as i32) + *r, (column as i32) + *c);
+        let mut possible_moves = self.possible_moves_to(from, bitboard::king_attacks(from), player_color);
 
-            if -1 < row1 && row1 < 8 {
+        possible_moves.extend(self.possible_castling(from, player_color));
 
-                if -1 < column1 && column1 < 8 { //Makes sure we stay on the board
+        possible_moves
 
-                    let square: u32 = row_column_to_square((row1 as u32, column1 as u32)); 
+    }
 
-                    match &self.board.squares[square as usize] { //We check if a piece is standing where we want to move
+    fn possible_castling(&self, from: Square, player_color: Color) -> Vec<Move> { //The king can also move two steps towards a rook if neither of them has moved yet
 
-                        Some(Piece) => {
-            
-                            if player_color != Piece.color{ // If an opponents piece is standing at the square the pawn wants to move to we can only go there if it is a diagonal square since the pawns only capture diagonally
+        let mut possible_moves: Vec<Move> = Vec::new();
 
-                                if *c != 0 { //The piece can only capture to the sides
+        let home_rank = match player_color {
+            Color::White => Rank::First,
+            Color::Black => Rank::Eighth,
+        };
 
-                                    possible_moves.push((row1 as u32, column1 as u32));
+        if from != Square::from_file_rank(File::E, home_rank) {
+            return possible_moves;
+        }
 
-                                }
-                            }
+        let opponent = player_color.opponent();
 
-                        }
+        let square = |file: File| Square::from_file_rank(file, home_rank);
 
-                        None => {
+        let rook_at = |file: File| matches!(
+            &self.board.squares[square(file).index()],
+            Some(piece) if piece.color == player_color && piece.piece_type == PieceType::Rook
+        );
+        let empty = |files: &[File]| files.iter().all(|&file| self.board.squares[square(file).index()].is_none());
+        let safe = |files: &[File]| files.iter().all(|&file| !self.is_attacked(square(file), opponent)); //The king may not castle out of, through or into check
 
-                            
-                            if *r == 2 { //The piece can move two steps forward if it hasnt moved before
-                                if row == 1 {
-                                    possible_moves.push((row1 as u32, column1 as u32));
-                                }
-                                else if row == 6{
-                                    possible_moves.push((row1 as u32, column1 as u32));   //We can use the rows 1 and 6 since the opposing players pieces cannot move two squares forward from the second to last rank since it will move them off the board      
-                                }
-                            }
-                            else {
-                                if *c == 0 { //The piece can only move straight if there is no other piece there
+        let rights = self.board.castling;
 
-                                    possible_moves.push((row1 as u32, column1 as u32));
-                                }
-                            }
-                            
-                        }
-                    }
-                
-                }
-            }
+        if rights.king_side(player_color) && rook_at(File::H) && empty(&[File::F, File::G]) && safe(&[File::E, File::F, File::G]) {
+            possible_moves.push(self.describe(from, square(File::G), None));
+        }
 
+        if rights.queen_side(player_color) && rook_at(File::A) && empty(&[File::B, File::C, File::D]) && safe(&[File::E, File::D, File::C]) {
+            possible_moves.push(self.describe(from, square(File::C), None));
         }
 
         possible_moves
 
-        //As a sidenote. I just noticed while writing the comments that it might be possible to move a pawn two squares if there is a piece standing one square in front. Havent tested this but it might be possible
+    }
+
+    pub fn possible_moves_knight(&self, from: Square, player_color: Color) -> Vec<Move> { //Returns all the possible moves of the knight
+
+        //Like the king. The knight also has pre determined moves it could make regardless of color, which are looked up in a table
+        self.possible_moves_to(from, bitboard::knight_attacks(from), player_color)
 
     }
 
-    pub fn legal_move(&mut self, to: (u32, u32), from: (u32,u32)) -> bool { //Sees if the move is legal. It is legal if the move does not lead to the players king being in check
+    fn possible_moves_to(&self, from: Square, attacks: Bitboard, player_color: Color) -> Vec<Move> { //Shared by every piece except the pawn, which can move to the squares it attacks
 
-        let mut simulation = self.clone(); //We simulate the move to see if it will make the players own king be checked.
-        
-        let from_square = row_column_to_square((from.0, from.1));
-        let to_square = row_column_to_square((to.0, to.1));
-        
-        simulation.make_move(from_square, to_square);
+        //The piece cant stand on top of another piece of the same color, but it can still capture the opponents piece.
+        let destinations = attacks & !self.board.get_color_pieces(player_color);
 
-        let player_color = match self.player { 
+        destinations.into_iter().map(|to| self.describe(from, to, None)).collect()
 
-            Player::BlackPlayer => Color::Black,
-            Player::WhitePlayer => Color::White,
+    }
+
+
+    pub fn possible_moves_rook(&self, from: Square, player_color: Color) -> Vec<Move> { //Returns all the possible moves of the rook
+
+        //Instead of specific moves the rook can move in certain directions until another piece or the edge of the board blocks it
+        self.possible_moves_to(from, bitboard::rook_attacks(from, self.board.get_occupied()), player_color)
+
+    }
+
+    pub fn possible_moves_bishop(&self, from: Square, player_color: Color) -> Vec<Move> { //Returns all the possible moves for the bishop
+
+        //Similarly to the rook, the bishop can move until another piece or the edge of the board blocks it in four directions. The only difference is that it moves idagonally.
+        self.possible_moves_to(from, bitboard::bishop_attacks(from, self.board.get_occupied()), player_color)
+
+    }
+
+
+    pub fn possible_moves_queen(&self, from: Square, player_color: Color) -> Vec<Move> { //Returns all the possible moves for the queen
+
+        //The queen has all the combined directions of the rook and the bishop
+        self.possible_moves_to(from, bitboard::queen_attacks(from, self.board.get_occupied()), player_color)
+
+    }
+
+
+    pub fn possible_moves_pawn(&self, from: Square, player_color: Color) -> Vec<Move> { //Returns all the possible moves of the pawn
+
+        //The pawn is difficult since it has different directions depending on the player since pawns can only move relatively forward.
+        let mut destinations: Vec<Square> = Vec::new();
+
+        let (forward, start_rank, last_rank) = match player_color { //White pawns move up the board and start on the second rank, black pawns move down and start on the seventh
+
+            Color::White => (1, Rank::Second, Rank::Eighth),
+            Color::Black => (-1, Rank::Seventh, Rank::First),
         };
 
-        let mut king_square = 0;
+        let is_empty = |square: &Square| self.board.squares[square.index()].is_none();
 
-        for i in 0..64 { //We need to find the square where the players king is
+        if let Some(one_step) = from.offset(0, forward).filter(is_empty) { //The pawn can only move straight if there is no other piece there
 
-            match &simulation.board.squares[i as usize] { //Check every single square and if the king is there we assign that particular square as the king square
+            destinations.push(one_step);
 
-                Some(piece) => {
+            if let Some(two_steps) = one_step.offset(0, forward).filter(|square| from.rank() == start_rank && is_empty(square)) { //The pawn can move two steps forward if it hasnt moved before, but only if the first square was free as well
+                destinations.push(two_steps);
+            }
+        }
 
-                    let piece_type = match piece.piece_type {
-                        
-                        PieceType::King => PieceType::King,
-                        PieceType::Queen => PieceType::Queen,
-                        PieceType::Bishop => PieceType::Bishop,
-                        PieceType::Knight => PieceType::Knight,
-                        PieceType::Rook => PieceType::Rook,
-                        PieceType::Pawn => PieceType::Pawn,
-        
-                        };
+        for square in bitboard::pawn_attacks(from, player_color) { //The pawn captures diagonally
 
-                    if player_color == piece.color && piece_type == PieceType::King { //This is the players king
+            match &self.board.squares[square.index()] {
 
-                        king_square = i;
-                            
-                    }
+                Some(piece) if piece.color != player_color => destinations.push(square),
 
+                None if self.board.en_passant == Some(square) && self.can_capture_en_passant(square, player_color) => { //The square an opponents pawn just skipped can be captured on like it was still there
+                    destinations.push(square);
                 }
-                None => {
-                }
+
+                _ => {}
             }
-            
         }
 
-        let mut possible_moves = Vec::new();
+        let mut possible_moves: Vec<Move> = Vec::new();
 
-        for i in 0..64 { //We check the moves of all the opponents pieces and if they can move to the square of the king that means the move is illegal
+        for to in destinations { //A pawn reaching the last row has to become another piece, which gives one move for each of them
 
-            match &simulation.board.squares[i as usize] {
+            if to.rank() == last_rank {
+                possible_moves.extend(PieceType::PROMOTIONS.iter().map(|&piece_type| self.describe(from, to, Some(piece_type))));
+            }
+            else {
+                possible_moves.push(self.describe(from, to, None));
+            }
+        }
 
-                Some(piece) => {
-    
-                    if player_color != piece.color{
+        possible_moves
 
-                        let opponent_white = match self.player { //This is the reason all the get moves functions have a color. We need to be able to call the functions for the players own pieces as well as the opponents pieces.
+    }
 
-                            Player::WhitePlayer => false,
-                            Player::BlackPlayer => true,
-                        };
+    fn can_capture_en_passant(&self, square: Square, player_color: Color) -> bool { //Only the opponents two step moves can be captured, which skip the third rank from their side
 
-                        possible_moves = simulation.get_possible_moves(i, opponent_white); //Gets all the moves of the opponents pieces
-                        
-                        for (row, column) in &possible_moves {
+        match player_color {
+            Color::White => square.rank() == Rank::Sixth,
+            Color::Black => square.rank() == Rank::Third,
+        }
+    }
 
-                            let move_square = row_column_to_square((*row, *column));
+    fn describe(&self, from: Square, to: Square, promotion: Option<PieceType>) -> Move { //Fills in what the piece on from does by moving to to, except for whether it gives check
 
-                            if move_square == king_square { //If a piee can attack the king it is an illegal move
+        let piece = self.board.squares[from.index()];
 
-                                return false;
-                                
-                            }
-                            
-                        }
-                    }
+        let pawn = matches!(piece, Some(piece) if piece.piece_type == PieceType::Pawn);
+        let king = matches!(piece, Some(piece) if piece.piece_type == PieceType::King);
 
-                }
-            
+        let files_moved = from.file().index().abs_diff(to.file().index());
 
-                None => {
+        let en_passant = pawn && files_moved == 1 && self.board.squares[to.index()].is_none(); //A pawn can only move sideways to an empty square by capturing en passant
 
-               }
-            }
+        let captured = match en_passant {
+            true => self.board.squares[Square::from_file_rank(to.file(), from.rank()).index()], //The captured pawn is beside the pawn and not on the destination
+            false => self.board.squares[to.index()],
+        };
+
+        Move {
+            from,
+            to,
+            promotion,
+            piece,
+            captured,
+            flags: MoveFlags {
+                castle: king && files_moved == 2,
+                en_passant,
+                double_push: pawn && from.rank().index().abs_diff(to.rank().index()) == 2,
+                check: false,
+            },
         }
+    }
+
+    /// Returns `true` if moving the piece on `from` to `to` does not leave the players own king in check.
+    /// The move is simulated, so this also catches an en passant capture removing two pieces from a rank the king is on.
+    pub fn legal_move(&self, from: Square, to: Square) -> bool {
 
-        return true;
+        let mut simulation = self.board.clone(); //We simulate the move on a copy of the board to see if it will make the players own king be checked.
+
+        simulation.apply_move(Move::new(from, to), self.promotion);
+
+        !simulation.is_in_check(self.player_color())
 
     }
-    
-    pub fn checkmate(&mut self) -> bool { //Sees if the player is in checkmate
-        
-        let player_color = match self.player {
 
-            Player::BlackPlayer => Color::Black,
-            Player::WhitePlayer => Color::White,
-        };
+    /// Returns `true` if the king of the given color is attacked by any of the opponents pieces.
+    pub fn is_in_check(&self, color: Color) -> bool {
 
-        let mut king_square = 0;
+        self.board.is_in_check(color)
+    }
 
-        for i in 0..64 { //We need to find the square where the king is
+    /// Returns `true` if any piece of the color `by` could capture on the given square.
+    pub fn is_attacked(&self, square: Square, by: Color) -> bool {
 
-            match &self.board.squares[i as usize] { //Check every single square and if the king is there we assign that particular square as the king square
+        self.board.is_attacked(square, by)
+    }
 
-                Some(Piece) => {
+    /// Returns every move of the piece on `from` that is allowed for the player whose turn it is.
+    /// A pawn reaching the last rank gives one move for each piece it can be promoted to.
+    pub fn get_legal_moves(&self, from: Square) -> Vec<Move> {
 
-                    let piece_type = match Piece.piece_type {
-                        
-                        PieceType::King => PieceType::King,
-                        PieceType::Queen => PieceType::Queen,
-                        PieceType::Bishop => PieceType::Bishop,
-                        PieceType::Knight => PieceType::Knight,
-                        PieceType::Rook => PieceType::Rook,
-                        PieceType::Pawn => PieceType::Pawn,
-        
-                        };
+        self.legal_moves_from(from)
+    }
 
-                    if player_color == Piece.color && piece_type == PieceType::King { //This is the players king
+    /// Returns every move the player whose turn it is can make, with the pieces they move and capture
+    /// and whether they give check filled in. There are no moves once the game is over.
+    pub fn legal_moves(&self) -> Vec<Move> {
 
-                        king_square = i;
-                            
-                    }
+        if self.state == GameState::GameOver {
+            return Vec::new();
+        }
 
-                }
-                None => {
-                }
-            }
-            
+        Square::all().flat_map(|from| self.get_legal_moves(from)).collect()
+    }
+
+    fn legal_moves_from(&self, from: Square) -> Vec<Move> {
+
+        match &self.board.squares[from.index()] {
+            Some(piece) if piece.color == self.player_color() => {}
+            _ => return Vec::new(),
         }
 
-        let mut possible_moves = Vec::new();
-        let mut all_moves = Vec::new();
+        let white = self.player == Player::WhitePlayer;
 
-        for i in 0..64 { //We check all the moves that the player can make and if there are no legal moves the player is in checkmate
-            //This for loop will go through all the players pieces and add all of their possible moves to the possible_moves vector. It will then check if the moves are legal and if they are the are added to the all_moves vector
+        let opponent_color = match white {
+            true => Color::Black,
+            false => Color::White,
+        };
 
-            match &self.board.squares[i as usize] {
+        self.get_possible_moves(from, white)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|mut mv| {
 
-                Some(Piece) => {
-    
-                    if player_color == Piece.color{
+                let mut simulation = self.board.clone(); //We simulate the move to see if it leaves the players own king in check, and whether it checks the opponents king
 
-                        let white = match self.player {
+                simulation.apply_move(mv, self.promotion);
 
-                            Player::WhitePlayer => true,
-                            Player::BlackPlayer => false,
-                        };
+                if simulation.is_in_check(self.player_color()) {
+                    return None;
+                }
 
-                        possible_moves = self.get_possible_moves(i, white);
-                        
-                        for (row, column) in &possible_moves {
+                mv.flags.check = simulation.is_in_check(opponent_color);
 
-                            let move_square = row_column_to_square((*row, *column));
+                Some(mv)
+            })
+            .collect()
 
-                            let from = square_to_row_column(i);
-                        
-                            let legal_move = self.legal_move((*row, *column), from);
-                            
-                            if legal_move == true {
+    }
 
-                                all_moves.push((*row, *column));
-                            }
-                        }
-                    }
+    /// Updates the state and outcome of the game for the player whose turn it is.
+    /// The game is over when the player has no legal moves, which is checkmate if they are in check and stalemate otherwise.
+    fn update_state(&mut self) {
 
-                }
-            
+        let in_check = self.is_in_check(self.player_color());
 
-                None => {
+        self.outcome = if !self.has_legal_moves() {
 
-               }
+            if in_check {
+                Some(GameOutcome::Decisive { winner: self.opponent(), reason: DecisiveReason::Checkmate })
+            }
+            else {
+                Some(GameOutcome::Draw { reason: DrawReason::Stalemate })
             }
         }
-
-        if all_moves.len() == 0 { //This means there are no possible moves for the player, they are in checkmate
-            println!("You are in checkmate!");
-            return true;
+        else if self.is_insufficient_material() {
+            Some(GameOutcome::Draw { reason: DrawReason::InsufficientMaterial })
+        }
+        else if self.halfmove_clock >= 150 {
+            Some(GameOutcome::Draw { reason: DrawReason::SeventyFiveMoveRule })
+        }
+        else if self.repetitions() >= 5 {
+            Some(GameOutcome::Draw { reason: DrawReason::FivefoldRepetition })
         }
         else {
-            return false;
+            None
+        };
+
+        self.state = if self.outcome.is_some() {
+            GameState::GameOver
         }
+        else if in_check {
+            GameState::Check
+        }
+        else {
+            GameState::InProgress
+        };
+
     }
 
-    pub fn whose_turn(&self) -> Player {
-        
-        self.player
+    /// How the game ended, or `None` if it is still in progress.
+    pub fn get_outcome(&self) -> Option<GameOutcome> {
+        self.outcome
     }
 
-    pub fn change_player(&mut self) {
+    /// The number of moves since the last capture or pawn move, counting the moves of both players.
+    pub fn get_halfmove_clock(&self) -> u32 {
+        self.halfmove_clock
+    }
 
-        self.player = match self.player {
+    /// Every move played in the game so far, in order.
+    pub fn get_moves(&self) -> Vec<Move> {
+        self.history.iter().map(|entry| entry.mv).collect()
+    }
 
-            Player::WhitePlayer => Player::BlackPlayer,
-            Player::BlackPlayer => Player::WhitePlayer,
-        }
+    /// The position the game started from as FEN, which is the normal starting position unless the game was set up with [`Game::from_fen`].
+    pub fn get_start_fen(&self) -> &str {
+        &self.start_fen
     }
-    
-}
 
-pub fn main() {
-    let mut game = Game::new(); //Initializes a new game with the board set up in the initial position, game state as active and player as white
+    /// The number of the current full move, which starts at 1 and goes up after every move by black.
+    pub fn get_fullmove_number(&self) -> u32 {
+        self.fullmove_number
+    }
 
-    println!("{:?}", game); //Prints the board in its starting position
+    /// Returns the rule the player to move can claim a draw by, if any.
+    /// This is the fifty-move rule or the current position having occurred three times.
+    pub fn can_claim_draw(&self) -> Option<DrawReason> {
 
-    let game_state = Game::get_game_state(&game); //Game state is in progress since it is set to that when we initialize the game
+        if self.outcome.is_some() {
+            None
+        }
+        else if self.halfmove_clock >= 100 {
+            Some(DrawReason::FiftyMoveRule)
+        }
+        else if self.repetitions() >= 3 {
+            Some(DrawReason::ThreefoldRepetition)
+        }
+        else {
+            None
+        }
+    }
 
-    while game_state != GameState::GameOver { //As long as the game is not game over we can play. Every time the loop restarts it is a new turn
+    /// Ends the game as a draw if the player to move can claim one, see [`Game::can_claim_draw`].
+    /// Returns the outcome of the game, or `None` if there was no draw to claim.
+    pub fn claim_draw(&mut self) -> Option<GameOutcome> {
 
-        let checkmate = Game::checkmate(&mut game); //Sees if you are in checkmate
+        let reason = self.can_claim_draw()?;
 
-        if checkmate == true { //If you are in checkmate the game ends
-            break;
-        }
+        self.outcome = Some(GameOutcome::Draw { reason });
+        self.state = GameState::GameOver;
 
-        let turn = Game::whose_turn(&game); //Lets us know whose turn it is
+        self.outcome
+    }
 
-        println!("It is {:?}'s turn", turn); //Prints whose turn it is
+    /// Returns `true` if neither player can ever checkmate, which is the case with only kings left,
+    /// a single bishop or knight against a lone king, or bishops that all stand on the same color of square.
+    pub fn is_insufficient_material(&self) -> bool {
 
-        let move_from = Game::from(&mut game); //Lets the player choose which piece to move
+        let mut minor_pieces = Vec::new();
 
-        let move_to = Game::to(&mut game, move_from); //Let sthe player choose where to move the piece and actually moves it.
+        for square in Square::all() {
 
-        println!("{:?}", game); //Prints the new positions of all the pieces
+            let Some(piece) = self.board.squares[square.index()] else { continue };
 
-        Game::change_player(&mut game); //Changes the player
+            match piece.piece_type {
+                PieceType::King => {}
+                PieceType::Bishop | PieceType::Knight => minor_pieces.push((square, piece.piece_type)),
+                _ => return false, //Any pawn, rook or queen can still give checkmate
+            }
+        }
 
+        match minor_pieces.as_slice() {
+            [] | [_] => true,
+            [(first, PieceType::Bishop), rest @ ..] => rest.iter().all(|&(square, piece_type)| {
+                piece_type == PieceType::Bishop && square.is_dark() == first.is_dark()
+            }),
+            _ => false,
+        }
     }
 
-    println!("Game over!"); //After the game is over we print this
+    fn repetitions(&self) -> usize { //How many times the current position has occurred
 
-}
+        //Positions from before the last capture or pawn move can never come back, so only the ones after it are looked at
+        let since_irreversible = self.halfmove_clock as usize + 1;
 
+        match self.positions.last() {
+            Some(current) => self.positions.iter().rev().take(since_irreversible).filter(|&key| key == current).count(), //Positions that are the same for the repetition rules have the same key
+            None => 0,
+        }
+    }
 
+    fn capturable_en_passant(&self) -> Option<Square> { //The en passant square only matters for repetitions if the player to move has a pawn that could capture there
 
+        let player_color = self.player_color();
 
+        self.board.en_passant.filter(|&square| {
 
+            let capturer_color = player_color.opponent();
 
+            !(bitboard::pawn_attacks(square, capturer_color) & self.board.get_pieces(player_color, PieceType::Pawn)).is_empty()
+        })
+    }
 
+    fn has_legal_moves(&self) -> bool { //Stops at the first legal move, without finding out if the moves give check
 
-pub fn square_to_row_column(square: u32) -> (u32, u32) { //Goes from the number notation ro the row column notation for the square
+        let white = self.player == Player::WhitePlayer;
 
-    let (row, column )  = (square/8, square%8);
+        Square::all()
+            .filter(|square| matches!(&self.board.squares[square.index()], Some(piece) if piece.color == self.player_color()))
+            .any(|square| self.get_possible_moves(square, white).unwrap_or_default().iter().any(|mv| self.legal_move(mv.from, mv.to)))
+    }
 
-    return (row, column);
-}
+    pub fn checkmate(&self) -> bool { //Sees if the player is in checkmate, which means they are in check and have no legal moves
 
-pub fn row_column_to_square((row, column): (u32, u32)) -> u32{ //Goes from row column notation to number notation for the square
+        self.is_in_check(self.player_color()) && !self.has_legal_moves()
+    }
 
-    let mut square = 0;
+    /// Returns `true` if the player to move is not in check but has no legal moves.
+    pub fn stalemate(&self) -> bool {
 
-    if row == 0 {
-        
-        square = column;
+        !self.is_in_check(self.player_color()) && !self.has_legal_moves()
     }
-    else {
 
-        square = ((row*8) + column).try_into().unwrap();
+    pub fn whose_turn(&self) -> Player {
+
+        self.player
     }
 
-    return square;
-}
+    pub fn change_player(&mut self) {
 
-pub fn convert_input_to_row_column() -> (u32, u32) { //Converts the user input of ""a3" to a row and a column
+        self.player = self.opponent();
+    }
 
-    let input = io::stdin(); 
-    
-    let mut place1 = input //Takes the users input and stores in a string
-        .lock()
-        .lines()
-        .map(|_line| _line.ok().unwrap())
-        .collect::<Vec<String>>();
+    fn opponent(&self) -> Player {
 
-    let characters: Vec<char> = place1.get(0).expect("REASON").chars().collect(); //Splits the string into two characters
+        match self.player {
 
-    let mut row = 0;
-    let mut column = 0;
+            Player::WhitePlayer => Player::BlackPlayer,
+            Player::BlackPlayer => Player::WhitePlayer,
+        }
+    }
 
-    if let Some(&letter) = characters.get(0) { //Pattern matches the character to columns
+    fn player_color(&self) -> Color { //The color of the pieces belonging to the player whose turn it is
 
-        match letter {
+        match self.player {
 
-            'a' => column = 0,
-            'b' => column = 1,
-            'c' => column = 2,
-            'd' => column = 3,
-            'e' => column = 4,
-            'f' => column = 5,
-            'g' => column = 6,
-            'h' => column = 7,
-            _ => println!("This is not a letter that matches a column"),
+            Player::WhitePlayer => Color::White,
+            Player::BlackPlayer => Color::Black,
         }
     }
-    else {
-        println!("Invalid character");
+
+}
+
+impl Default for Game {
+
+    fn default() -> Self {
+        Game::new()
     }
+}
+
+pub fn main() {
+    let mut game = Game::new(); //Initializes a new game with the board set up in the initial position, game state as active and player as white
 
+    println!("{:?}", game); //Prints the board in its starting position
 
-    if let Some(&digit) = characters.get(1) { //We check so the index is correctly given. Inputs like os wont break the program
-        if let Some(number) = digit.to_digit(10) {
-            row = number -1;
-        } else {
-            println!("Invalid character!");
-        }
-    } else {
-        println!("Invalid character");
+    while game.get_game_state() != GameState::GameOver { //As long as the game is not game over we can play. Every time the loop restarts it is a new turn
+
+        let turn = Game::whose_turn(&game); //Lets us know whose turn it is
+
+        println!("It is {:?}'s turn", turn); //Prints whose turn it is
+
+        let move_from = Game::from(&mut game); //Lets the player choose which piece to move
+
+        Game::to(&mut game, move_from); //Lets the player choose where to move the piece and actually moves it. This also passes the turn to the other player.
+
+        println!("{:?}", game); //Prints the new positions of all the pieces
+
+    }
+
+    match game.get_outcome() { //Tells the players how the game ended
+        Some(GameOutcome::Decisive { winner, reason }) => println!("{:?} won by {:?}!", winner, reason),
+        Some(GameOutcome::Draw { reason }) => println!("The game is a draw by {:?}!", reason),
+        None => (),
     }
 
-    return (row, column)
+    println!("Game over!"); //After the game is over we print this
 
 }
 
 
-pub fn convert_row_column_to_output(row: u32, column: u32) -> (char, u32){ //Convers the row column notation to an output like a3
 
 
-    let row1 = row+1;
-    let mut letter: char = ' ';
-    
 
-    match column {
 
-        0 => letter = 'a',
-        1 => letter = 'b',
-        2 => letter = 'c',
-        3 => letter = 'd',
-        4 => letter = 'e',
-        5 => letter = 'f',
-        6 => letter = 'g',
-        7 => letter = 'h',
-        _ => (),
-    }
 
-    return (letter, row1)
 
+fn read_input() -> String { //Reads one line typed by the user, without the line break
+
+    let mut line = String::new();
+
+    io::stdin().lock().read_line(&mut line).expect("could not read from the terminal");
 
+    line.trim().to_string()
+}
+
+/// Reads a square like "a3" typed by the user. Anything else gives `None`.
+pub fn read_square() -> Option<Square> {
+
+    parse_square(&read_input())
+}
+
+/// Reads a square like "a3" from text typed by the user, the way [`read_square`] does. Anything else gives `None`.
+pub fn parse_square(text: &str) -> Option<Square> {
+
+    text.trim().parse().ok()
 }
 
 
@@ -1124,7 +1490,7 @@ pub fn convert_row_column_to_output(row: u32, column: u32) -> (char, u32){ //Con
 
 
 /// Implement print routine for Game.
-/// 
+///
 /// Output example:
 /// |:----------------------:|
 /// | R  Kn B  K  Q  B  Kn R |
@@ -1168,20 +1534,16 @@ impl fmt::Debug for Game {
                     board_string.push_str(piece_str);
                 } else {
 
-                    board_string.push(' ');
-                    board_string.push(' ');
-                    board_string.push('*');
-                    board_string.push(' ');
-                    board_string.push(' ');
+                    board_string.push_str("  *  ");
                 }
             }
             board_string.push('\n');
         }
 
         write!(f, "{}", board_string)
-        
+
     }
-        
+
 }
 
 
@@ -1193,11 +1555,47 @@ impl fmt::Debug for Game {
 
 #[cfg(test)]
 mod tests {
-    use crate::convert_input_to_row_column;
-    use crate::square_to_row_column;
-
+    use crate::parse_square;
+
+    use super::Bitboard;
+    use super::Board;
+    use super::CastlingRights;
+    use super::Color;
+    use super::DecisiveReason;
+    use super::DrawReason;
     use super::Game;
+    use super::GameOutcome;
     use super::GameState;
+    use super::Move;
+    use super::MoveError;
+    use super::Piece;
+    use super::PieceType;
+    use super::Player;
+    use super::Square;
+    use super::SquareError;
+
+    // sets up a game with only the given pieces on the board
+    fn game_with_pieces(pieces: &[(u32, Color, PieceType)], player: Player) -> Game {
+
+        let mut game = Game::new();
+
+        let mut squares = [None; 64];
+
+        for &(square, color, piece_type) in pieces {
+            squares[square as usize] = Some(Piece::new(color, piece_type));
+        }
+
+        game.board = Board::from_squares(squares, game.board.castling, None);
+
+        game.player = player;
+        game.positions = vec![game.get_hash()];
+        game
+    }
+
+    fn piece_at(game: &Game, square: usize) -> Option<(Color, PieceType)> {
+
+        game.get_board().get_squares()[square].map(|piece| (piece.get_color(), piece.get_piece_type()))
+    }
 
     // check test framework
     #[test]
@@ -1208,16 +1606,18 @@ mod tests {
     #[test]
     fn conversion() {
 
-        let conversion = square_to_row_column(10);
-        assert_eq!(conversion, (1,2));
+        let conversion = Square::new(10).unwrap();
+        assert_eq!((conversion.rank().index(), conversion.file().index()), (1,2));
     }
 
     #[test]
 
     fn input_square() {
 
-        let square = convert_input_to_row_column();
-        assert_eq!(square, (0,0));
+        assert_eq!(parse_square("a1"), Some(Square::A1));
+        assert_eq!(parse_square(" h8\n"), Some(Square::H8));
+        assert_eq!(parse_square("i9"), None);
+        assert_eq!(parse_square(""), None);
     }
     // example test
     // check that game state is in progress after initialisation
@@ -1230,4 +1630,678 @@ mod tests {
 
         assert_eq!(game.get_game_state(), GameState::InProgress);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn play_moves_piece_and_passes_turn() {
+
+        let mut game = Game::new();
+
+        assert_eq!(game.play(Square::E2, Square::E4), Ok(GameState::InProgress)); // e2 to e4
+        assert_eq!(game.whose_turn(), Player::BlackPlayer);
+        assert!(game.get_board().get_squares()[12].is_none());
+        assert!(game.get_board().get_squares()[28].is_some());
+    }
+
+    #[test]
+    fn play_rejects_invalid_moves() {
+
+        let mut game = Game::new();
+
+        assert_eq!(game.play(Square::E3, Square::E4), Err(MoveError::EmptySquare(Square::E3)));
+        assert_eq!(game.play(Square::E7, Square::E6), Err(MoveError::OpponentsPiece(Square::E7)));
+        assert_eq!(game.play(Square::A1, Square::A2), Err(MoveError::IllegalDestination(Square::A2)));
+        assert_eq!(Square::try_from(64), Err(SquareError::OutOfRange(64))); // squares off the board can not even be made
+        assert_eq!(game.whose_turn(), Player::WhitePlayer);
+    }
+
+    #[test]
+    fn play_rejects_moves_that_leave_king_in_check() {
+
+        let mut game = Game::new();
+
+        for (from, to) in [(Square::E2, Square::E4), (Square::F7, Square::F6)] { // e4 f6
+            game.play(from, to).unwrap();
+        }
+
+        assert_eq!(game.play(Square::D1, Square::H5), Ok(GameState::Check)); // Qh5+
+        assert_eq!(game.play(Square::A7, Square::A6), Err(MoveError::LeavesKingInCheck)); // a6 ignores the check
+        assert_eq!(game.play(Square::G7, Square::G6), Ok(GameState::InProgress)); // g6 blocks it
+    }
+
+    #[test]
+    fn play_ends_game_after_fools_mate() {
+
+        let mut game = Game::new();
+
+        for (from, to) in [(Square::F2, Square::F3), (Square::E7, Square::E5), (Square::G2, Square::G4), (Square::D8, Square::H4)] { // f3 e5 g4 Qh4#
+            game.play(from, to).unwrap();
+        }
+
+        assert_eq!(game.get_game_state(), GameState::GameOver);
+        assert_eq!(game.get_outcome(), Some(GameOutcome::Decisive { winner: Player::BlackPlayer, reason: DecisiveReason::Checkmate }));
+        assert!(game.checkmate());
+        assert_eq!(game.play(Square::A2, Square::A3), Err(MoveError::GameOver));
+    }
+
+    #[test]
+    fn play_as_rejects_player_out_of_turn() {
+
+        let mut game = Game::new();
+
+        assert_eq!(game.play_as(Player::BlackPlayer, Square::E7, Square::E5), Err(MoveError::NotYourTurn(Player::BlackPlayer)));
+        assert_eq!(game.play_as(Player::WhitePlayer, Square::E2, Square::E4), Ok(GameState::InProgress));
+        assert_eq!(game.play_as(Player::BlackPlayer, Square::E7, Square::E5), Ok(GameState::InProgress));
+    }
+
+    #[test]
+    fn move_errors_describe_the_problem() {
+
+        let game = Game::new();
+
+        assert_eq!(game.get_possible_moves(Square::E4, true), Err(MoveError::EmptySquare(Square::E4)));
+        assert_eq!(game.validate_move(Square::E4, Square::E5).unwrap_err().to_string(), "there is no piece at e4");
+        assert_eq!(game.validate_move(Square::B1, Square::D2).unwrap_err().to_string(), "the piece cannot move to d2");
+        assert_eq!(MoveError::NotYourTurn(Player::BlackPlayer).to_string(), "it is not BlackPlayer's turn");
+    }
+
+    #[test]
+    fn castling_king_side_moves_the_rook() {
+
+        let mut game = Game::new();
+
+        for (from, to) in [(Square::E2, Square::E4), (Square::E7, Square::E5), (Square::G1, Square::F3), (Square::B8, Square::C6), (Square::F1, Square::C4), (Square::F8, Square::C5)] { // e4 e5 Nf3 Nc6 Bc4 Bc5
+            game.play(from, to).unwrap();
+        }
+
+        assert_eq!(game.play(Square::E1, Square::G1), Ok(GameState::InProgress));
+        assert_eq!(piece_at(&game, 6), Some((Color::White, PieceType::King)));
+        assert_eq!(piece_at(&game, 5), Some((Color::White, PieceType::Rook)));
+        assert_eq!(piece_at(&game, 7), None);
+
+        let rights = game.get_board().get_castling_rights();
+        assert!(!rights.white_king_side && !rights.white_queen_side);
+        assert!(rights.black_king_side && rights.black_queen_side);
+    }
+
+    #[test]
+    fn castling_queen_side_moves_the_rook() {
+
+        let mut game = game_with_pieces(&[
+            (4, Color::White, PieceType::King), (0, Color::White, PieceType::Rook),
+            (60, Color::Black, PieceType::King), (56, Color::Black, PieceType::Rook),
+        ], Player::BlackPlayer);
+
+        assert_eq!(game.play(Square::E8, Square::C8), Ok(GameState::InProgress));
+        assert_eq!(piece_at(&game, 58), Some((Color::Black, PieceType::King)));
+        assert_eq!(piece_at(&game, 59), Some((Color::Black, PieceType::Rook)));
+        assert_eq!(piece_at(&game, 56), None);
+    }
+
+    #[test]
+    fn castling_not_allowed_out_of_through_or_into_check() {
+
+        let mut pieces = vec![
+            (4, Color::White, PieceType::King), (0, Color::White, PieceType::Rook), (7, Color::White, PieceType::Rook),
+            (63, Color::Black, PieceType::King),
+        ];
+
+        let game = game_with_pieces(&pieces, Player::WhitePlayer);
+        assert!(game.validate_move(Square::E1, Square::G1).is_ok());
+        assert!(game.validate_move(Square::E1, Square::C1).is_ok());
+
+        pieces.push((61, Color::Black, PieceType::Rook)); // attacks f1 which the king passes
+        let game = game_with_pieces(&pieces, Player::WhitePlayer);
+        assert_eq!(game.validate_move(Square::E1, Square::G1), Err(MoveError::IllegalDestination(Square::G1)));
+        assert!(game.validate_move(Square::E1, Square::C1).is_ok());
+
+        pieces.pop();
+        pieces.push((50, Color::Black, PieceType::Rook)); // attacks c1 where the king would land
+        let game = game_with_pieces(&pieces, Player::WhitePlayer);
+        assert!(game.validate_move(Square::E1, Square::G1).is_ok());
+        assert_eq!(game.validate_move(Square::E1, Square::C1), Err(MoveError::IllegalDestination(Square::C1)));
+
+        pieces.pop();
+        pieces.push((60, Color::Black, PieceType::Rook)); // the king is in check
+        let game = game_with_pieces(&pieces, Player::WhitePlayer);
+        assert!(game.validate_move(Square::E1, Square::G1).is_err());
+        assert!(game.validate_move(Square::E1, Square::C1).is_err());
+
+        pieces.pop();
+        pieces.push((1, Color::White, PieceType::Knight)); // b1 is only passed by the rook, but has to be empty
+        let game = game_with_pieces(&pieces, Player::WhitePlayer);
+        assert_eq!(game.validate_move(Square::E1, Square::C1), Err(MoveError::IllegalDestination(Square::C1)));
+    }
+
+    #[test]
+    fn castling_rights_lost_when_rook_moves_or_is_captured() {
+
+        let mut game = game_with_pieces(&[
+            (4, Color::White, PieceType::King), (0, Color::White, PieceType::Rook), (7, Color::White, PieceType::Rook),
+            (60, Color::Black, PieceType::King), (56, Color::Black, PieceType::Rook), (63, Color::Black, PieceType::Rook),
+        ], Player::WhitePlayer);
+
+        game.play(Square::H1, Square::H8).unwrap(); // Rxh8+ captures the rook in the corner
+        game.play(Square::E8, Square::D7).unwrap(); // Kd7
+        game.play(Square::H8, Square::H7).unwrap(); // Rh7+ and then back to h8 later cannot restore the right
+        game.play(Square::D7, Square::C6).unwrap();
+        game.play(Square::H7, Square::H8).unwrap();
+
+        assert_eq!(game.get_board().get_castling_rights(), CastlingRights {
+            white_king_side: false,
+            white_queen_side: true,
+            black_king_side: false,
+            black_queen_side: false,
+        });
+        assert_eq!(game.validate_move(Square::C6, Square::E6), Err(MoveError::IllegalDestination(Square::E6)));
+    }
+
+    #[test]
+    fn en_passant_captures_the_pawn_beside() {
+
+        let mut game = Game::new();
+
+        for (from, to) in [(Square::E2, Square::E4), (Square::A7, Square::A6), (Square::E4, Square::E5), (Square::D7, Square::D5)] { // e4 a6 e5 d5
+            game.play(from, to).unwrap();
+        }
+
+        assert_eq!(game.get_board().get_en_passant(), Some(Square::D6));
+        assert!(game.get_possible_moves(Square::E5, true).unwrap().contains(&Move::new(Square::E5, Square::D6)));
+
+        assert_eq!(game.play(Square::E5, Square::D6), Ok(GameState::InProgress)); // exd6
+        assert_eq!(piece_at(&game, 43), Some((Color::White, PieceType::Pawn)));
+        assert_eq!(piece_at(&game, 35), None);
+        assert_eq!(game.get_board().get_en_passant(), None);
+    }
+
+    #[test]
+    fn en_passant_only_on_the_next_move() {
+
+        let mut game = Game::new();
+
+        for (from, to) in [(Square::E2, Square::E4), (Square::A7, Square::A6), (Square::E4, Square::E5), (Square::D7, Square::D5), (Square::H2, Square::H3), (Square::H7, Square::H6)] { // e4 a6 e5 d5 h3 h6
+            game.play(from, to).unwrap();
+        }
+
+        assert_eq!(game.play(Square::E5, Square::D6), Err(MoveError::IllegalDestination(Square::D6)));
+    }
+
+    #[test]
+    fn en_passant_not_allowed_when_it_exposes_the_king_along_the_rank() {
+
+        let mut game = game_with_pieces(&[
+            (32, Color::White, PieceType::King), (33, Color::White, PieceType::Pawn),
+            (63, Color::Black, PieceType::King), (50, Color::Black, PieceType::Pawn), (39, Color::Black, PieceType::Rook),
+        ], Player::BlackPlayer);
+
+        game.play(Square::C7, Square::C5).unwrap(); // c5, the pawn on c5 now shields the king together with the pawn on b5
+
+        assert_eq!(game.get_board().get_en_passant(), Some(Square::C6));
+        assert_eq!(game.validate_move(Square::B5, Square::C6), Err(MoveError::LeavesKingInCheck));
+    }
+
+    fn promotion_game() -> Game {
+
+        game_with_pieces(&[
+            (4, Color::White, PieceType::King), (49, Color::White, PieceType::Pawn),
+            (63, Color::Black, PieceType::King), (56, Color::Black, PieceType::Rook),
+        ], Player::WhitePlayer)
+    }
+
+    #[test]
+    fn promotion_defaults_to_queen() {
+
+        let mut game = promotion_game();
+
+        game.play(Square::B7, Square::B8).unwrap(); // b8=Q
+        assert_eq!(piece_at(&game, 57), Some((Color::White, PieceType::Queen)));
+    }
+
+    #[test]
+    fn promotion_uses_piece_from_set_promotion() {
+
+        let mut game = promotion_game();
+
+        assert_eq!(game.set_promotion("dragon"), Err(MoveError::InvalidPromotion));
+        assert_eq!(game.set_promotion("Knight"), Ok(()));
+
+        game.play(Square::B7, Square::A8).unwrap(); // bxa8=N
+        assert_eq!(piece_at(&game, 56), Some((Color::White, PieceType::Knight)));
+    }
+
+    #[test]
+    fn promotion_piece_given_with_the_move() {
+
+        let mut game = promotion_game();
+
+        assert_eq!(game.play_move(Move::with_promotion(Square::B7, Square::B8, PieceType::King)), Err(MoveError::InvalidPromotion));
+        assert_eq!(game.play_move(Move::with_promotion(Square::E1, Square::E2, PieceType::Queen)), Err(MoveError::InvalidPromotion));
+
+        game.play_move(Move::with_promotion(Square::B7, Square::B8, PieceType::Rook)).unwrap();
+        assert_eq!(piece_at(&game, 57), Some((Color::White, PieceType::Rook)));
+        assert_eq!(piece_at(&game, 49), None);
+    }
+
+    #[test]
+    fn every_promotion_is_a_separate_move() {
+
+        let game = promotion_game();
+
+        let moves = game.get_legal_moves(Square::B7);
+
+        assert_eq!(moves.len(), 8);
+        for piece_type in PieceType::PROMOTIONS {
+            assert!(moves.contains(&Move::with_promotion(Square::B7, Square::A8, piece_type)));
+            assert!(moves.contains(&Move::with_promotion(Square::B7, Square::B8, piece_type)));
+        }
+        assert!(game.get_legal_moves(Square::E1).iter().all(|mv| mv.promotion.is_none()));
+    }
+
+    #[test]
+    fn stalemate_is_a_draw_and_not_checkmate() {
+
+        let mut game = game_with_pieces(&[
+            (63, Color::Black, PieceType::King), (45, Color::White, PieceType::King), (38, Color::White, PieceType::Queen),
+        ], Player::WhitePlayer);
+
+        assert_eq!(game.play(Square::G5, Square::G6), Ok(GameState::GameOver)); // Qg6 leaves the king on h8 without moves
+        assert_eq!(game.get_outcome(), Some(GameOutcome::Draw { reason: DrawReason::Stalemate }));
+        assert!(game.stalemate());
+        assert!(!game.checkmate());
+    }
+
+    #[test]
+    fn check_state_is_kept_up_to_date() {
+
+        let mut game = Game::new();
+
+        for (from, to) in [(Square::E2, Square::E4), (Square::F7, Square::F6), (Square::D1, Square::H5)] { // e4 f6 Qh5+
+            game.play(from, to).unwrap();
+        }
+
+        assert_eq!(game.get_game_state(), GameState::Check);
+        assert_eq!(game.get_outcome(), None);
+        assert!(!game.checkmate());
+
+        game.play(Square::G7, Square::G6).unwrap(); // g6
+        assert_eq!(game.get_game_state(), GameState::InProgress);
+    }
+
+    // the sorted destination squares of the legal moves of the piece on `from`
+    fn destinations(game: &Game, from: u32) -> Vec<u32> {
+
+        let mut squares: Vec<u32> = game.get_legal_moves(Square::new(from).unwrap()).iter().map(|mv| u32::from(mv.to)).collect();
+
+        squares.sort();
+        squares.dedup();
+        squares
+    }
+
+    #[test]
+    fn pawn_cannot_jump_over_a_blocking_piece() {
+
+        let game = game_with_pieces(&[
+            (4, Color::White, PieceType::King), (60, Color::Black, PieceType::King),
+            (12, Color::White, PieceType::Pawn), (20, Color::Black, PieceType::Knight), // e2 blocked on e3
+            (11, Color::White, PieceType::Pawn), (27, Color::Black, PieceType::Knight), // d2 blocked on d4 only
+        ], Player::WhitePlayer);
+
+        assert_eq!(destinations(&game, 12), vec![]);
+        assert_eq!(destinations(&game, 11), vec![19, 20]);
+    }
+
+    #[test]
+    fn pawn_double_step_only_from_own_starting_rank() {
+
+        let game = game_with_pieces(&[
+            (4, Color::White, PieceType::King), (60, Color::Black, PieceType::King),
+            (9, Color::Black, PieceType::Pawn), (54, Color::White, PieceType::Pawn),
+        ], Player::BlackPlayer);
+
+        assert_eq!(destinations(&game, 9), vec![1]); // b2 only steps to b1 (and promotes)
+
+        let mut game = game;
+        game.change_player();
+        assert_eq!(destinations(&game, 54), vec![62]); // g7 only steps to g8
+    }
+
+    #[test]
+    fn pawn_captures_diagonally_but_not_forward() {
+
+        let game = game_with_pieces(&[
+            (4, Color::White, PieceType::King), (60, Color::Black, PieceType::King),
+            (28, Color::White, PieceType::Pawn), (36, Color::Black, PieceType::Pawn),
+            (35, Color::Black, PieceType::Bishop), (37, Color::White, PieceType::Knight),
+        ], Player::WhitePlayer);
+
+        assert_eq!(destinations(&game, 28), vec![35]);
+    }
+
+    #[test]
+    fn knight_jumps_over_pieces_but_not_onto_own() {
+
+        let game = Game::new();
+
+        assert_eq!(destinations(&game, 1), vec![16, 18]);
+
+        let game = game_with_pieces(&[
+            (4, Color::White, PieceType::King), (60, Color::Black, PieceType::King),
+            (0, Color::White, PieceType::Knight), (10, Color::White, PieceType::Pawn), (17, Color::Black, PieceType::Pawn),
+        ], Player::WhitePlayer);
+
+        assert_eq!(destinations(&game, 0), vec![17]);
+    }
+
+    #[test]
+    fn sliding_pieces_stop_at_the_first_piece() {
+
+        let game = game_with_pieces(&[
+            (0, Color::White, PieceType::King), (63, Color::Black, PieceType::King),
+            (27, Color::White, PieceType::Rook), (30, Color::Black, PieceType::Pawn), (11, Color::White, PieceType::Pawn),
+            (18, Color::White, PieceType::Bishop), (36, Color::Black, PieceType::Knight),
+        ], Player::WhitePlayer);
+
+        assert_eq!(destinations(&game, 27), vec![19, 24, 25, 26, 28, 29, 30, 35, 43, 51, 59]); // d4 rook
+        assert_eq!(destinations(&game, 18), vec![9, 25, 32]); // c3 bishop, blocked by the own king, rook and pawn
+    }
+
+    #[test]
+    fn queen_on_open_board_reaches_27_squares() {
+
+        let game = game_with_pieces(&[
+            (7, Color::White, PieceType::King), (57, Color::Black, PieceType::King), (27, Color::White, PieceType::Queen),
+        ], Player::WhitePlayer);
+
+        assert_eq!(destinations(&game, 27).len(), 27); // d4 queen, neither king is on its lines
+    }
+
+    #[test]
+    fn king_cannot_move_into_check_or_capture_defended_piece() {
+
+        let game = game_with_pieces(&[
+            (4, Color::White, PieceType::King), (60, Color::Black, PieceType::King),
+            (12, Color::Black, PieceType::Pawn), (20, Color::Black, PieceType::Knight), // the knight on e3 attacks d1 and f1
+            (15, Color::Black, PieceType::Rook), // h2 rook attacks f2 and defends the pawn on e2
+        ], Player::WhitePlayer);
+
+        assert_eq!(destinations(&game, 4), vec![11]); // only d2 is safe, the rook is stopped by the pawn
+    }
+
+    #[test]
+    fn pinned_piece_can_only_move_along_the_pin() {
+
+        let game = game_with_pieces(&[
+            (4, Color::White, PieceType::King), (63, Color::Black, PieceType::King),
+            (20, Color::White, PieceType::Rook), (44, Color::Black, PieceType::Queen), // rook e3 pinned by queen e6
+            (13, Color::White, PieceType::Knight), (31, Color::Black, PieceType::Bishop), // knight f2 pinned by bishop h4
+        ], Player::WhitePlayer);
+
+        assert_eq!(destinations(&game, 20), vec![12, 28, 36, 44]);
+        assert_eq!(destinations(&game, 13), vec![]);
+    }
+
+    // Nf3 Nf6 Ng1 Ng8, which brings back the starting position
+    const KNIGHT_SHUFFLE: [(Square, Square); 4] = [(Square::G1, Square::F3), (Square::G8, Square::F6), (Square::F3, Square::G1), (Square::F6, Square::G8)];
+
+    #[test]
+    fn threefold_repetition_can_be_claimed() {
+
+        let mut game = Game::new();
+
+        for _ in 0..2 {
+            for (from, to) in KNIGHT_SHUFFLE {
+                game.play(from, to).unwrap();
+            }
+        }
+
+        assert_eq!(game.get_game_state(), GameState::InProgress);
+        assert_eq!(game.can_claim_draw(), Some(DrawReason::ThreefoldRepetition));
+        assert_eq!(game.claim_draw(), Some(GameOutcome::Draw { reason: DrawReason::ThreefoldRepetition }));
+        assert_eq!(game.get_game_state(), GameState::GameOver);
+        assert_eq!(game.play(Square::E2, Square::E4), Err(MoveError::GameOver));
+    }
+
+    #[test]
+    fn fivefold_repetition_ends_the_game() {
+
+        let mut game = Game::new();
+
+        assert_eq!(game.claim_draw(), None);
+
+        for _ in 0..4 {
+            for (from, to) in KNIGHT_SHUFFLE {
+                game.play(from, to).unwrap();
+            }
+        }
+
+        assert_eq!(game.get_outcome(), Some(GameOutcome::Draw { reason: DrawReason::FivefoldRepetition }));
+    }
+
+    #[test]
+    fn pawn_move_resets_clock_and_repetitions() {
+
+        let mut game = Game::new();
+
+        for (from, to) in KNIGHT_SHUFFLE {
+            game.play(from, to).unwrap();
+        }
+        assert_eq!(game.get_halfmove_clock(), 4);
+
+        game.play(Square::E2, Square::E4).unwrap(); // e4
+        assert_eq!(game.get_halfmove_clock(), 0);
+
+        for (from, to) in [(Square::G8, Square::F6), (Square::G1, Square::F3), (Square::F6, Square::G8), (Square::F3, Square::G1), (Square::G8, Square::F6), (Square::G1, Square::F3), (Square::F6, Square::G8), (Square::F3, Square::G1)] {
+            game.play(from, to).unwrap();
+        }
+        assert_eq!(game.can_claim_draw(), Some(DrawReason::ThreefoldRepetition)); // the position after e4 occurred three times
+    }
+
+    #[test]
+    fn fifty_and_seventy_five_move_rules() {
+
+        let mut game = Game::new();
+
+        game.halfmove_clock = 99;
+        game.play(Square::G1, Square::F3).unwrap();
+        assert_eq!(game.can_claim_draw(), Some(DrawReason::FiftyMoveRule));
+        assert_eq!(game.get_game_state(), GameState::InProgress);
+
+        game.halfmove_clock = 149;
+        game.play(Square::G8, Square::F6).unwrap();
+        assert_eq!(game.get_outcome(), Some(GameOutcome::Draw { reason: DrawReason::SeventyFiveMoveRule }));
+    }
+
+    #[test]
+    fn insufficient_material_ends_the_game() {
+
+        let mut game = game_with_pieces(&[
+            (4, Color::White, PieceType::King), (13, Color::Black, PieceType::Knight),
+            (60, Color::Black, PieceType::King), (11, Color::Black, PieceType::Pawn),
+        ], Player::WhitePlayer);
+
+        assert!(!game.is_insufficient_material());
+        game.play(Square::E1, Square::D2).unwrap(); // Kxd2 leaves a king and knight against a king
+        assert_eq!(game.get_outcome(), Some(GameOutcome::Draw { reason: DrawReason::InsufficientMaterial }));
+
+        let same_colored_bishops = game_with_pieces(&[
+            (4, Color::White, PieceType::King), (2, Color::White, PieceType::Bishop),
+            (60, Color::Black, PieceType::King), (61, Color::Black, PieceType::Bishop),
+        ], Player::WhitePlayer);
+        assert!(same_colored_bishops.is_insufficient_material());
+
+        let opposite_colored_bishops = game_with_pieces(&[
+            (4, Color::White, PieceType::King), (5, Color::White, PieceType::Bishop),
+            (60, Color::Black, PieceType::King), (61, Color::Black, PieceType::Bishop),
+        ], Player::WhitePlayer);
+        assert!(!opposite_colored_bishops.is_insufficient_material());
+
+        let two_knights = game_with_pieces(&[
+            (4, Color::White, PieceType::King), (1, Color::White, PieceType::Knight), (6, Color::White, PieceType::Knight),
+            (60, Color::Black, PieceType::King),
+        ], Player::WhitePlayer);
+        assert!(!two_knights.is_insufficient_material());
+    }
+
+    // finds the legal move between the two squares, promoting to a queen
+    fn legal_move_between(game: &Game, from: Square, to: Square) -> Move {
+
+        *game.legal_moves().iter().find(|mv| mv.from == from && mv.to == to && mv.promotion.unwrap_or(PieceType::Queen) == PieceType::Queen).unwrap()
+    }
+
+    #[test]
+    fn legal_moves_carry_pieces_and_flags() {
+
+        let game = Game::from_fen("r3k2r/1P6/8/3pP3/8/8/P7/R3K2R w KQkq d6 0 1").unwrap();
+
+        let en_passant = legal_move_between(&game, Square::E5, Square::D6);
+        assert_eq!(en_passant.piece, Some(Piece::new(Color::White, PieceType::Pawn)));
+        assert_eq!(en_passant.captured, Some(Piece::new(Color::Black, PieceType::Pawn)));
+        assert!(en_passant.flags.en_passant && en_passant.is_capture());
+
+        let castle = legal_move_between(&game, Square::E1, Square::G1);
+        assert!(castle.flags.castle && !castle.is_capture());
+
+        let double_push = legal_move_between(&game, Square::A2, Square::A4);
+        assert!(double_push.flags.double_push && !double_push.flags.check);
+
+        let promotion = legal_move_between(&game, Square::B7, Square::A8);
+        assert_eq!(promotion.captured, Some(Piece::new(Color::Black, PieceType::Rook)));
+        assert!(promotion.flags.check);
+
+        assert_eq!(game.legal_moves().iter().filter(|mv| mv.flags.check).count(), 5); // bxa8=Q, bxa8=R, b8=Q, b8=R and Rxh8
+        assert_eq!(game.legal_moves().iter().filter(|mv| mv.from == Square::B7).count(), 8);
+    }
+
+    #[test]
+    fn played_moves_are_recorded_with_metadata() {
+
+        let mut game = Game::new();
+
+        for (from, to) in [(Square::F2, Square::F3), (Square::E7, Square::E5), (Square::G2, Square::G4), (Square::D8, Square::H4)] { // f3 e5 g4 Qh4#
+            game.play(from, to).unwrap();
+        }
+
+        let mate = game.get_moves()[3];
+        assert_eq!(mate.piece, Some(Piece::new(Color::Black, PieceType::Queen)));
+        assert!(mate.flags.check);
+        assert!(game.get_moves()[1].flags.double_push);
+        assert_eq!(mate, Move::new(Square::D8, Square::H4));
+
+        assert!(game.legal_moves().is_empty());
+    }
+
+    #[test]
+    fn undo_restores_special_moves() {
+
+        let start = "r3k2r/1P6/8/3pP3/8/8/8/R3K2R w KQkq d6 0 12";
+        let mut game = Game::from_fen(start).unwrap();
+
+        let mut fens = vec![game.to_fen()];
+
+        for (from, to) in [(Square::E5, Square::D6), (Square::E8, Square::G8), (Square::B7, Square::A8), (Square::F8, Square::A8), (Square::E1, Square::C1)] { // exd6 O-O bxa8=Q+ Rxa8 O-O-O
+            game.play(from, to).unwrap();
+            fens.push(game.to_fen());
+        }
+
+        assert_eq!(game.ply(), 5);
+        assert_eq!(game.history()[0].en_passant, Some(Square::D6));
+        assert_eq!(game.history()[2].mv.captured, Some(Piece::new(Color::Black, PieceType::Rook)));
+        assert_eq!(game.history()[3].mv.captured, Some(Piece::new(Color::White, PieceType::Queen)));
+
+        while let Some(mv) = game.undo_move() {
+            fens.pop();
+            assert_eq!(&game.to_fen(), fens.last().unwrap(), "after taking back {}", mv);
+        }
+
+        assert_eq!(game.to_fen(), start);
+        assert_eq!(game.ply(), 0);
+
+        assert_eq!(game.redo_move(), Some(Move::new(Square::E5, Square::D6)));
+        assert!(game.go_to_ply(5));
+        assert_eq!(game.get_board().get_piece(Square::D1), Some(Piece::new(Color::White, PieceType::Rook)));
+        assert!(!game.go_to_ply(6));
+    }
+
+    // every square of the mailbox has to be in the bitboards of its piece and in no others
+    fn assert_bitboards_match(game: &Game) {
+
+        let board = game.get_board();
+
+        for square in Square::all() {
+            for color in [Color::White, Color::Black] {
+                for piece_type in [PieceType::Pawn, PieceType::Rook, PieceType::Knight, PieceType::Bishop, PieceType::Queen, PieceType::King] {
+
+                    let expected = board.get_piece(square) == Some(Piece::new(color, piece_type));
+
+                    assert_eq!(board.get_pieces(color, piece_type).contains(square), expected, "{:?} {:?} on {}", color, piece_type, square);
+                }
+            }
+        }
+
+        assert_eq!(board.get_occupied().count() as usize, board.get_squares().iter().flatten().count());
+        assert_eq!(board.get_occupied(), board.get_color_pieces(Color::White) | board.get_color_pieces(Color::Black));
+    }
+
+    #[test]
+    fn bitboards_follow_the_pieces() {
+
+        let mut game = Game::from_fen("r3k2r/1P6/8/3pP3/8/8/8/R3K2R w KQkq d6 0 12").unwrap();
+
+        assert_bitboards_match(&game);
+        assert_eq!(game.get_board().get_pieces(Color::White, PieceType::Rook), Bitboard::from(Square::A1) | Bitboard::from(Square::H1));
+
+        for (from, to) in [(Square::E5, Square::D6), (Square::E8, Square::G8), (Square::B7, Square::A8), (Square::F8, Square::A8), (Square::E1, Square::C1)] { // exd6 O-O bxa8=Q+ Rxa8 O-O-O
+            game.play(from, to).unwrap();
+            assert_bitboards_match(&game);
+        }
+
+        while game.undo_move().is_some() {
+            assert_bitboards_match(&game);
+        }
+
+        assert_bitboards_match(&Game::new());
+        assert_eq!(Game::new().get_board().get_occupied().count(), 32);
+    }
+
+    #[test]
+    fn undo_takes_back_the_end_of_the_game() {
+
+        let mut game = Game::new();
+
+        for (from, to) in [(Square::F2, Square::F3), (Square::E7, Square::E5), (Square::G2, Square::G4), (Square::D8, Square::H4)] { // f3 e5 g4 Qh4#
+            game.play(from, to).unwrap();
+        }
+
+        assert_eq!(game.get_game_state(), GameState::GameOver);
+
+        assert_eq!(game.undo_move(), Some(Move::new(Square::D8, Square::H4)));
+        assert_eq!(game.get_game_state(), GameState::InProgress);
+        assert_eq!(game.get_outcome(), None);
+        assert_eq!(game.whose_turn(), Player::BlackPlayer);
+        assert_eq!(game.get_fullmove_number(), 2);
+
+        game.play(Square::B8, Square::C6).unwrap(); // a different move forgets the mate
+        assert_eq!(game.redo_move(), None);
+        assert!(game.go_to_ply(0));
+        assert_eq!(game.undo_move(), None);
+        assert_eq!(game.to_fen(), Game::new().to_fen());
+    }
+
+    #[test]
+    fn undone_positions_do_not_count_as_repetitions() {
+
+        let mut game = Game::new();
+
+        for (from, to) in KNIGHT_SHUFFLE.iter().chain(KNIGHT_SHUFFLE.iter()) {
+            game.play(*from, *to).unwrap();
+        }
+
+        assert_eq!(game.can_claim_draw(), Some(DrawReason::ThreefoldRepetition));
+
+        game.undo_move();
+        assert_eq!(game.can_claim_draw(), None);
+        assert_eq!(game.get_halfmove_clock(), 7);
+
+        game.redo_move();
+        assert_eq!(game.claim_draw(), Some(GameOutcome::Draw { reason: DrawReason::ThreefoldRepetition }));
+    }
+}
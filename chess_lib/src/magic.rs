@@ -0,0 +1,287 @@
+//! Rook and bishop attacks looked up in constant time with "magic" bitboards.
+//!
+//! Only the pieces on the rays of a slider can block it, and the squares on the edge of the board never
+//! block anything behind them. Multiplying the pieces on those squares (the mask) with a magic number
+//! brings the bits that matter together in the top bits of the product, which is then used as an
+//! index into a table of attacks worked out beforehand with the ray walk in [`crate::bitboard`].
+//!
+//! A magic number works if it gives every blocker set its own index, or an index shared only with blocker
+//! sets that have the same attacks. The numbers below were found by trying random numbers until one worked,
+//! with the search in the tests of this module. The tables are filled the first time they are used.
+
+use std::sync::OnceLock;
+
+use crate::bitboard::{self, Bitboard};
+use crate::Square;
+
+
+struct Magic {
+    mask: Bitboard, //The squares that can block the slider, without the edges
+    magic: u64,
+    shift: u32, //64 minus the number of squares in the mask, which leaves one index for every blocker set
+    offset: usize, //Where the attacks of this square start in the shared table
+}
+
+impl Magic {
+
+    fn index(&self, occupied: Bitboard) -> usize {
+
+        self.offset + ((occupied & self.mask).0.wrapping_mul(self.magic) >> self.shift) as usize
+    }
+}
+
+struct Magics {
+    rooks: Vec<Magic>,
+    bishops: Vec<Magic>,
+    attacks: Vec<Bitboard>, //The attacks of every square and blocker set for both pieces
+}
+
+//The magic numbers for each square from a1 to h8, found by find_magic in the tests with the seed 0x9e3779b97f4a7c15
+const ROOK_MAGICS: [u64; 64] = [
+    0x1080_0040_0880_1020, 0x0840_0920_02c0_3000, 0x1900_2000_1040_0900, 0x0880_1000_0800_0480,
+    0x4200_1004_2008_0200, 0x8100_0201_0008_0400, 0x0200_0401_1088_6200, 0x0200_0080_4022_0411,
+    0x0404_8000_8440_0220, 0x0000_4010_0040_2000, 0x0086_0010_8122_0440, 0x0408_8008_0010_0280,
+    0x000a_0012_0104_0820, 0x8848_8002_0084_0080, 0x4001_0001_0004_0200, 0x0442_0001_0210_5084,
+    0x9080_0100_2080_4100, 0x0040_4040_0020_1009, 0x0000_8080_1000_2009, 0x2200_0900_21d0_0100,
+    0x0008_0080_0804_0080, 0x0004_0040_0201_0040, 0x0011_0400_0801_5042, 0x0000_0a00_0176_8104,
+    0x0000_8000_8020_4009, 0x2010_0041_4000_2001, 0x9800_2002_8010_0080, 0x1000_1000_8008_0080,
+    0x0442_000a_0004_9020, 0x2100_0400_8002_0080, 0x0800_1204_0090_0148, 0x0010_040a_0012_8541,
+    0x2800_8040_0080_0030, 0x1010_0020_0040_0041, 0x4000_2000_1100_4100, 0x0610_0084_1080_0800,
+    0x0400_8024_0280_0800, 0xc100_0200_8080_0400, 0x0002_0008_0200_0401, 0x0182_0858_8200_0401,
+    0x0220_2040_0080_8000, 0x2860_1000_4002_4022, 0x0001_0020_0411_0040, 0x9910_1042_000a_0020,
+    0x0004_0800_0400_8080, 0x0010_0400_0200_8080, 0x2012_0048_8102_0004, 0x8300_8424_4482_0011,
+    0x0088_4038_8201_0200, 0x0820_4000_8021_0100, 0x0110_9100_40a0_0300, 0x0801_1002_8008_0480,
+    0x0242_0090_0820_0600, 0x1002_0004_8950_0200, 0x0040_8002_0001_0080, 0x0091_8000_4100_0080,
+    0x0000_2093_0048_8001, 0x04c1_0024_1482_4001, 0x0200_2000_0b00_1041, 0x7000_1000_0420_0901,
+    0x8002_0020_0410_0802, 0x3001_0002_084c_0007, 0x0888_2218_0081_3004, 0x4000_0028_4084_0112,
+];
+
+const BISHOP_MAGICS: [u64; 64] = [
+    0xa010_0411_0800_3100, 0x0060_8202_0a00_2900, 0x6810_0106_1920_0000, 0x0828_1a05_2000_0408,
+    0x0001_1040_0100_0400, 0x0018_9010_0804_8400, 0x0004_0a02_1024_5280, 0x0002_0021_0808_a402,
+    0x9140_0484_1082_1200, 0x0800_0910_1082_0041, 0x2050_4804_8322_02c0, 0x0100_0914_0108_1000,
+    0x8021_0111_4000_0012, 0x0810_0208_0445_0400, 0x208b_0542_1090_08a2, 0x0080_084a_0804_0204,
+    0x0040_e2a8_0811_244c, 0x2505_0220_0800_8108, 0x0430_2201_0042_0040, 0x010a_0404_2022_0040,
+    0x1105_0002_9040_0000, 0x0093_0012_0082_2120, 0x4000_a620_4804_3004, 0x2801_2004_8a01_5004,
+    0x0060_9000_2a02_0814, 0x4404_2000_2408_00d0, 0x0110_2800_040a_4400, 0x1004_0800_8022_0040,
+    0x0001_0010_1100_4024, 0x0010_0440_0080_5040, 0x0914_0412_0082_0100, 0x0004_8210_1282_1480,
+    0x0024_0405_00c0_5021, 0x0088_6110_0208_0200, 0x0116_080a_0004_0020, 0x4000_0200_8008_0080,
+    0x2450_4501_4084_0040, 0x0000_8802_0148_4100, 0x0222_0204_0402_0092, 0x8081_1106_0000_2e00,
+    0x2842_1011_0500_0801, 0x1100_8090_0800_1025, 0x0002_0202_221c_0400, 0x0422_0140_2200_9020,
+    0x0210_0461_0210_0c00, 0xc004_0080_8202_9102, 0x00aa_4618_0110_1200, 0x0404_0800_8020_1108,
+    0x0205_4210_8c20_5002, 0x0410_5448_0410_0100, 0x0040_9108_4110_0000, 0x0400_2000_4202_1100,
+    0x0000_4204_8504_00c0, 0x0200_1004_10a4_2102, 0x1040_0208_0121_0102, 0x0805_0404_1042_0000,
+    0x2884_8041_3010_0200, 0x800c_2622_0124_2000, 0x1058_0001_9410_8800, 0x0014_2210_5442_0204,
+    0x0104_0000_12a0_2200, 0x0200_8810_0330_0100, 0x0140_4002_0284_0100, 0x0402_0208_0101_0201,
+];
+
+static MAGICS: OnceLock<Magics> = OnceLock::new();
+
+fn magics() -> &'static Magics {
+
+    MAGICS.get_or_init(|| {
+
+        let mut attacks = Vec::new();
+
+        let rooks = Square::all().map(|square| fill_attacks(square, ROOK_MAGICS[square.index()], bitboard::rook_rays, &mut attacks)).collect();
+        let bishops = Square::all().map(|square| fill_attacks(square, BISHOP_MAGICS[square.index()], bitboard::bishop_rays, &mut attacks)).collect();
+
+        Magics { rooks, bishops, attacks }
+    })
+}
+
+/// The squares a rook on `square` attacks, see [`bitboard::rook_attacks`].
+pub(crate) fn rook_attacks(square: Square, occupied: Bitboard) -> Bitboard {
+
+    let magics = magics();
+
+    magics.attacks[magics.rooks[square.index()].index(occupied)]
+}
+
+/// The squares a bishop on `square` attacks, see [`bitboard::bishop_attacks`].
+pub(crate) fn bishop_attacks(square: Square, occupied: Bitboard) -> Bitboard {
+
+    let magics = magics();
+
+    magics.attacks[magics.bishops[square.index()].index(occupied)]
+}
+
+fn fill_attacks(square: Square, magic: u64, rays: fn(Square, Bitboard) -> Bitboard, attacks: &mut Vec<Bitboard>) -> Magic { //Adds the attacks for every blocker set of the square to the table
+
+    let mask = mask(square, rays);
+
+    let magic = Magic { mask, magic, shift: 64 - mask.count(), offset: attacks.len() };
+
+    attacks.resize(attacks.len() + (1 << mask.count()), Bitboard::EMPTY);
+
+    for blocking in subsets(mask) {
+        attacks[magic.index(blocking)] = rays(square, blocking);
+    }
+
+    magic
+}
+
+fn mask(square: Square, rays: fn(Square, Bitboard) -> Bitboard) -> Bitboard { //The squares that can block a slider on the square
+
+    rays(square, Bitboard::EMPTY) & !edges(square)
+}
+
+fn subsets(mask: Bitboard) -> impl Iterator<Item = Bitboard> { //Every subset of the mask, found by counting through the bits of the mask, starting with the empty set
+
+    let mut subset = Some(Bitboard::EMPTY);
+
+    std::iter::from_fn(move || {
+
+        let current = subset?;
+        let next = Bitboard(current.0.wrapping_sub(mask.0) & mask.0);
+
+        subset = if next.is_empty() { None } else { Some(next) };
+
+        Some(current)
+    })
+}
+
+fn edges(square: Square) -> Bitboard { //The outer files and ranks, except the ones the square is on since a slider can move along those to the edge
+
+    let rank_1 = Bitboard(0xff);
+    let rank_8 = Bitboard(0xff << 56);
+    let file_a = Bitboard(0x0101_0101_0101_0101);
+    let file_h = Bitboard(0x0101_0101_0101_0101 << 7);
+
+    let rank = Bitboard(0xff << (8 * square.rank().index()));
+    let file = Bitboard(0x0101_0101_0101_0101 << square.file().index());
+
+    ((rank_1 | rank_8) & !rank) | ((file_a | file_h) & !file)
+}
+
+
+
+
+// --------------------------
+// ######### TESTS ##########
+// --------------------------
+
+#[cfg(test)]
+mod tests {
+    use crate::bitboard::{self, Bitboard};
+    use crate::Square;
+
+    use super::{bishop_attacks, edges, mask, rook_attacks, subsets, BISHOP_MAGICS, ROOK_MAGICS};
+
+    // a xorshift generator, which is all the randomness finding magic numbers needs
+    struct Random(u64);
+
+    impl Random {
+
+        fn next(&mut self) -> u64 {
+
+            self.0 ^= self.0 >> 12;
+            self.0 ^= self.0 << 25;
+            self.0 ^= self.0 >> 27;
+
+            self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+        }
+
+        // numbers with few bits set make good magic numbers much more often
+        fn sparse(&mut self) -> u64 {
+
+            self.next() & self.next() & self.next()
+        }
+    }
+
+    // tries random numbers until one gives the blocker sets of the square indices that only collide when the attacks are the same
+    fn find_magic(square: Square, rays: fn(Square, Bitboard) -> Bitboard, random: &mut Random) -> u64 {
+
+        let mask = mask(square, rays);
+        let shift = 64 - mask.count();
+
+        let blockers: Vec<(Bitboard, Bitboard)> = subsets(mask).map(|blocking| (blocking, rays(square, blocking))).collect();
+
+        let mut attacks = vec![Bitboard::EMPTY; 1 << mask.count()];
+        let mut used = vec![0; 1 << mask.count()]; // the try in which each index was last filled, so the table does not have to be cleared
+        let mut attempt = 0;
+
+        loop {
+
+            let magic = random.sparse();
+
+            if (mask.0.wrapping_mul(magic) >> 56).count_ones() < 6 { // numbers that spread too few bits to the top can never work
+                continue;
+            }
+
+            attempt += 1;
+
+            let fits = blockers.iter().all(|&(blocking, attacked)| {
+
+                let index = (blocking.0.wrapping_mul(magic) >> shift) as usize;
+
+                if used[index] != attempt {
+                    used[index] = attempt;
+                    attacks[index] = attacked;
+                    true
+                }
+                else {
+                    attacks[index] == attacked
+                }
+            });
+
+            if fits {
+                return magic;
+            }
+        }
+    }
+
+    #[test]
+    fn search_finds_the_magic_numbers() {
+
+        let mut random = Random(0x9e37_79b9_7f4a_7c15);
+
+        // the rooks come first, so only the first few squares are searched again to keep the test quick
+        for square in Square::all().take(4) {
+            assert_eq!(find_magic(square, bitboard::rook_rays, &mut random), ROOK_MAGICS[square.index()], "rook on {}", square);
+        }
+
+        assert_ne!(BISHOP_MAGICS, [0; 64]);
+    }
+
+    #[test]
+    fn matches_the_ray_walk_for_every_blocker_set() {
+
+        for square in Square::all() {
+
+            for blocking in subsets(mask(square, bitboard::rook_rays)) {
+                assert_eq!(rook_attacks(square, blocking), bitboard::rook_rays(square, blocking), "{} with {:?}", square, blocking);
+            }
+
+            for blocking in subsets(mask(square, bitboard::bishop_rays)) {
+                assert_eq!(bishop_attacks(square, blocking), bitboard::bishop_rays(square, blocking), "{} with {:?}", square, blocking);
+            }
+        }
+    }
+
+    #[test]
+    fn matches_the_ray_walk_on_random_boards() {
+
+        let mut random = Random(1);
+
+        for _ in 0..2000 {
+
+            let occupied = Bitboard(random.next() & random.next()); // about a quarter of the squares are taken, like in a game
+
+            for square in Square::all() {
+                assert_eq!(rook_attacks(square, occupied), bitboard::rook_rays(square, occupied), "{} with {:?}", square, occupied);
+                assert_eq!(bishop_attacks(square, occupied), bitboard::bishop_rays(square, occupied), "{} with {:?}", square, occupied);
+            }
+        }
+    }
+
+    #[test]
+    fn masks_leave_out_the_edges() {
+
+        assert_eq!(edges(Square::D4).count(), 28);
+        assert_eq!(mask(Square::A1, bitboard::rook_rays).count(), 12);
+        assert_eq!(mask(Square::E4, bitboard::rook_rays).count(), 10);
+        assert_eq!(mask(Square::D4, bitboard::bishop_rays).count(), 9);
+        assert_eq!(subsets(mask(Square::A1, bitboard::rook_rays)).count(), 4096);
+    }
+}
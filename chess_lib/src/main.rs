@@ -0,0 +1,73 @@
+use std::env;
+use std::process;
+
+use chess_lib::search::SearchLimits;
+use chess_lib::Game;
+
+const USAGE: &str = "usage: chess_lib [play]
+       chess_lib perft <depth> [fen]
+       chess_lib divide <depth> [fen]
+       chess_lib search <depth> [fen]";
+
+fn main() {
+
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    match args.first().map(String::as_str) {
+
+        None | Some("play") => chess_lib::main(), //Without a subcommand two players play against each other in the terminal
+
+        Some(command @ ("perft" | "divide" | "search")) => {
+
+            let depth: u32 = match args.get(1).map(|depth| depth.parse()) {
+                Some(Ok(depth)) => depth,
+                _ => exit_with(USAGE),
+            };
+
+            let game = match args.len() { //The FEN is given as the rest of the arguments, with or without quotes
+                0..=2 => Game::new(),
+                _ => Game::from_fen(&args[2..].join(" ")).unwrap_or_else(|error| exit_with(&error.to_string())),
+            };
+
+            if command == "perft" {
+                println!("{}", game.perft(depth));
+            }
+            else if command == "search" {
+
+                let result = game.search(SearchLimits::depth(depth));
+                let pv: Vec<String> = result.pv.iter().map(|mv| mv.to_string()).collect();
+
+                let score = match result.mate_in() { //Written like the scores of UCI engines
+                    Some(moves) => format!("mate {}", moves),
+                    None => format!("cp {}", result.score),
+                };
+
+                println!("depth {} score {} nodes {} time {} hashfull {} pv {}", result.depth, score, result.nodes, result.time.as_millis(), result.hashfull, pv.join(" "));
+
+                match result.best_move {
+                    Some(mv) => println!("bestmove {}", mv),
+                    None => println!("bestmove (none)"),
+                }
+            }
+            else {
+
+                let divide = game.perft_divide(depth);
+
+                for (mv, count) in &divide {
+                    println!("{}: {}", mv, count);
+                }
+
+                println!();
+                println!("Nodes searched: {}", divide.iter().map(|(_, count)| count).sum::<u64>());
+            }
+        }
+
+        Some(_) => exit_with(USAGE),
+    }
+}
+
+fn exit_with(message: &str) -> ! {
+
+    eprintln!("{}", message);
+    process::exit(2);
+}
@@ -0,0 +1,519 @@
+//! The order the search tries the moves of a position in. Alpha-beta cuts off the most lines when the best
+//! move is tried first, so the moves most likely to be good come first:
+//!
+//! 1. the best move found for the position before, the "hash move"
+//! 2. captures that do not lose material, the most valuable victim first and then the least valuable attacker (MVV-LVA)
+//! 3. the killer moves, quiet moves that were too good for the opponent in another position at the same depth
+//! 4. the counter move, the quiet move that was too good the last time the opponent played the move they just played
+//! 5. the other quiet moves, the ones that were too good most often first (the history heuristic)
+//! 6. captures that lose material
+//!
+//! [`MovePicker`] hands out the moves in this order and only generates the quiet moves once the captures are used up,
+//! since a capture often cuts off the rest.
+
+use crate::bitboard;
+use crate::search::MAX_PLY;
+use crate::{Color, Game, Move, PieceType};
+
+
+const VICTIM_RANKS: [i32; 6] = [1, 4, 2, 3, 5, 6]; //The pieces from the least to the most valuable, indexed by PieceType
+const HISTORY_LIMIT: i32 = 1 << 20; //The history scores are halved once one of them gets this high, so newer cutoffs count more than old ones
+
+
+/// The scores for the killer, counter move and history heuristics, learned from the cutoffs during a search.
+#[derive(Clone, Debug)]
+pub struct MoveOrdering {
+    killers: Vec<[Option<Move>; 2]>, //Two for each ply, the newest first
+    history: Vec<i32>, //Indexed by the color moving, the square the move starts from and the square it goes to
+    counter_moves: Vec<Option<Move>>, //Indexed by the squares of the move they answer
+}
+
+impl MoveOrdering {
+
+    pub fn new() -> MoveOrdering {
+
+        MoveOrdering {
+            killers: vec![[None; 2]; MAX_PLY as usize + 1],
+            history: vec![0; 2 * 64 * 64],
+            counter_moves: vec![None; 64 * 64],
+        }
+    }
+
+    /// Forgets everything learned so far.
+    pub fn clear(&mut self) {
+
+        *self = MoveOrdering::new();
+    }
+
+    /// Remembers a quiet move that was too good for the opponent, `ply` half moves into the search and with
+    /// `depth` half moves left to search, as the answer to the `previous` move of the opponent.
+    pub fn add_cutoff(&mut self, color: Color, mv: Move, depth: u32, ply: u32, previous: Option<Move>) {
+
+        let killers = &mut self.killers[ply as usize];
+
+        if killers[0] != Some(mv) {
+            killers[1] = killers[0];
+            killers[0] = Some(mv);
+        }
+
+        let index = history_index(color, mv);
+
+        self.history[index] += (depth * depth) as i32; //A cutoff deep in the tree saves more work than one near the leaves
+
+        if self.history[index] > HISTORY_LIMIT {
+            self.history.iter_mut().for_each(|score| *score /= 2);
+        }
+
+        if let Some(previous) = previous {
+            self.counter_moves[previous.from.index() * 64 + previous.to.index()] = Some(mv);
+        }
+    }
+
+    /// The two killer moves at the ply, the newest first.
+    pub fn get_killers(&self, ply: u32) -> [Option<Move>; 2] {
+
+        self.killers[ply as usize]
+    }
+
+    /// How often, and how deep, the move caused a cutoff when played by `color`.
+    pub fn get_history(&self, color: Color, mv: Move) -> i32 {
+
+        self.history[history_index(color, mv)]
+    }
+
+    /// The quiet move that last caused a cutoff right after the opponent played `previous`.
+    pub fn get_counter_move(&self, previous: Move) -> Option<Move> {
+
+        self.counter_moves[previous.from.index() * 64 + previous.to.index()]
+    }
+}
+
+impl Default for MoveOrdering {
+
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn history_index(color: Color, mv: Move) -> usize {
+
+    (color as usize * 64 + mv.from.index()) * 64 + mv.to.index()
+}
+
+
+/// The score of a capture by its most valuable victim and least valuable attacker: taking a queen with a pawn
+/// comes before taking it with a rook, which comes before taking a rook with anything. Promotions count the
+/// piece the pawn becomes as a victim. Moves that are neither score 0.
+pub fn mvv_lva(mv: &Move) -> i32 {
+
+    let victim = mv.captured.map_or(0, |piece| VICTIM_RANKS[piece.piece_type as usize]);
+    let promotion = mv.promotion.map_or(0, |piece_type| VICTIM_RANKS[piece_type as usize]);
+    let attacker = mv.piece.map_or(0, |piece| VICTIM_RANKS[piece.piece_type as usize]);
+
+    match victim + promotion {
+        0 => 0,
+        value => value * 8 - attacker,
+    }
+}
+
+/// The captures and promotions of the player to move, without looking at whether they leave the king in check.
+pub fn capture_moves(game: &Game) -> Vec<Move> {
+
+    generate(game, true)
+}
+
+/// The moves of the player to move that are neither captures nor promotions, castling included,
+/// without looking at whether they leave the king in check.
+pub fn quiet_moves(game: &Game) -> Vec<Move> {
+
+    generate(game, false)
+}
+
+fn generate(game: &Game, captures: bool) -> Vec<Move> {
+
+    let board = &game.board;
+    let color = game.player_color();
+
+    let opponent = color.opponent();
+
+    let targets = match captures {
+        true => board.get_color_pieces(opponent),
+        false => !board.get_occupied(),
+    };
+
+    let mut moves = Vec::new();
+
+    for from in board.get_color_pieces(color) {
+
+        let Some(piece) = board.get_piece(from) else { continue };
+
+        let attacks = match piece.piece_type {
+
+            PieceType::Pawn => { //Pawns capture differently from how they move, so their moves are sorted out after making them
+                moves.extend(game.possible_moves_pawn(from, color).into_iter().filter(|mv| (mv.is_capture() || mv.promotion.is_some()) == captures));
+                continue;
+            }
+
+            PieceType::King => {
+
+                if !captures {
+                    moves.extend(game.possible_castling(from, color));
+                }
+
+                bitboard::king_attacks(from)
+            }
+
+            PieceType::Knight => bitboard::knight_attacks(from),
+            PieceType::Bishop => bitboard::bishop_attacks(from, board.get_occupied()),
+            PieceType::Rook => bitboard::rook_attacks(from, board.get_occupied()),
+            PieceType::Queen => bitboard::queen_attacks(from, board.get_occupied()),
+        };
+
+        moves.extend((attacks & targets).into_iter().map(|to| game.describe(from, to, None)));
+    }
+
+    moves
+}
+
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Stage {
+    HashMove,
+    GenerateCaptures,
+    Captures,
+    Killers,
+    CounterMove,
+    Quiets,
+    LosingCaptures,
+    Done,
+}
+
+/// Hands out the moves of a position one at a time in the order described in the [module documentation](self).
+///
+/// The moves can still leave the king in check, which the search finds out when it plays them.
+/// Every move is handed out once, even if it is the hash move and a killer move at the same time.
+#[derive(Clone, Debug)]
+pub struct MovePicker<'a> {
+    game: &'a Game,
+    stage: Stage,
+    captures_only: bool, //For the quiescence search, which only wants the captures that do not lose material
+    hash_move: Option<Move>,
+    killers: [Option<Move>; 2],
+    counter_move: Option<Move>,
+    moves: Vec<(i32, Move)>, //The moves of the current stage with their scores, the best one last
+    losing_captures: Vec<(i32, Move)>,
+    quiets: Vec<Move>,
+    handed_out: Vec<Move>, //The hash, killer and counter moves, which are left out when their stage comes around again
+}
+
+impl<'a> MovePicker<'a> {
+
+    /// Every move of the position, starting with `hash_move` if it is a move in the position. The killer and counter moves
+    /// are taken from `ordering` for a position `ply` half moves into the search, reached by the opponent playing `previous`.
+    pub fn new(game: &'a Game, hash_move: Option<Move>, ordering: &MoveOrdering, ply: u32, previous: Option<Move>) -> MovePicker<'a> {
+
+        MovePicker {
+            game,
+            stage: Stage::HashMove,
+            captures_only: false,
+            hash_move,
+            killers: ordering.get_killers(ply),
+            counter_move: previous.and_then(|previous| ordering.get_counter_move(previous)),
+            moves: Vec::new(),
+            losing_captures: Vec::new(),
+            quiets: Vec::new(),
+            handed_out: Vec::new(),
+        }
+    }
+
+    /// For the quiescence search: only the promotions and the captures that do not lose material by [`Game::static_exchange_eval`].
+    pub fn captures(game: &'a Game) -> MovePicker<'a> {
+
+        MovePicker {
+            game,
+            stage: Stage::GenerateCaptures,
+            captures_only: true,
+            hash_move: None,
+            killers: [None; 2],
+            counter_move: None,
+            moves: Vec::new(),
+            losing_captures: Vec::new(),
+            quiets: Vec::new(),
+            handed_out: Vec::new(),
+        }
+    }
+
+    /// The next move, or `None` once every move has been handed out. The quiet moves are sorted by
+    /// the history scores in `ordering` when their stage is reached.
+    pub fn next(&mut self, ordering: &MoveOrdering) -> Option<Move> {
+
+        loop {
+
+            match self.stage {
+
+                Stage::HashMove => {
+
+                    self.stage = Stage::GenerateCaptures;
+
+                    let hash_move = self.hash_move.and_then(|mv| self.find(mv)); //A move from the table can come from another position with the same slot
+
+                    if let Some(mv) = hash_move {
+                        self.handed_out.push(mv);
+                        return Some(mv);
+                    }
+                }
+
+                Stage::GenerateCaptures => {
+
+                    self.start_captures();
+                    self.stage = Stage::Captures;
+                }
+
+                Stage::Captures => match self.moves.pop() {
+                    Some((_, mv)) if self.handed_out.contains(&mv) => continue,
+                    Some((_, mv)) => return Some(mv),
+                    None if self.captures_only => self.stage = Stage::Done,
+                    None => {
+                        self.quiets = quiet_moves(self.game);
+                        self.stage = Stage::Killers;
+                    }
+                },
+
+                Stage::Killers => {
+
+                    let killers = self.killers;
+
+                    match killers.into_iter().flatten().find_map(|killer| self.take_quiet(killer)) { //A killer that is handed out is no longer among the quiet moves, so the next time the other one is found
+                        Some(mv) => return Some(mv),
+                        None => self.stage = Stage::CounterMove,
+                    }
+                }
+
+                Stage::CounterMove => {
+
+                    let counter_move = self.counter_move.and_then(|counter| self.take_quiet(counter));
+
+                    self.start_quiets(ordering);
+                    self.stage = Stage::Quiets;
+
+                    if counter_move.is_some() {
+                        return counter_move;
+                    }
+                }
+
+                Stage::Quiets => match self.moves.pop() {
+                    Some((_, mv)) if self.handed_out.contains(&mv) => continue,
+                    Some((_, mv)) => return Some(mv),
+                    None => {
+                        self.moves = std::mem::take(&mut self.losing_captures);
+                        self.stage = Stage::LosingCaptures;
+                    }
+                },
+
+                Stage::LosingCaptures => match self.moves.pop() {
+                    Some((_, mv)) if self.handed_out.contains(&mv) => continue,
+                    Some((_, mv)) => return Some(mv),
+                    None => self.stage = Stage::Done,
+                },
+
+                Stage::Done => return None,
+            }
+        }
+    }
+
+    fn start_captures(&mut self) { //Splits the captures into the ones that win or trade material and the ones that lose it
+
+        for mv in capture_moves(self.game) {
+
+            let score = mvv_lva(&mv);
+
+            if mv.promotion.is_some() || self.game.static_exchange_eval(mv) >= 0 {
+                self.moves.push((score, mv));
+            }
+            else if !self.captures_only {
+                self.losing_captures.push((score, mv));
+            }
+        }
+
+        self.moves.sort_by_key(|&(score, _)| score);
+        self.losing_captures.sort_by_key(|&(score, _)| score);
+    }
+
+    fn start_quiets(&mut self, ordering: &MoveOrdering) {
+
+        let color = self.game.player_color();
+
+        self.moves = self.quiets.drain(..).map(|mv| (ordering.get_history(color, mv), mv)).collect();
+        self.moves.sort_by_key(|&(score, _)| score);
+    }
+
+    fn take_quiet(&mut self, mv: Move) -> Option<Move> { //The quiet move if it can be played here and was not handed out yet
+
+        let index = self.quiets.iter().position(|&quiet| quiet == mv)?;
+        let mv = self.quiets.swap_remove(index);
+
+        match self.handed_out.contains(&mv) {
+            true => None,
+            false => {
+                self.handed_out.push(mv);
+                Some(mv)
+            }
+        }
+    }
+
+    fn find(&self, mv: Move) -> Option<Move> { //The move as generated in this position, if the piece on its square belongs to the player to move and can make it
+
+        let white = self.game.player_color() == Color::White;
+
+        match self.game.board.get_piece(mv.from) {
+            Some(piece) if piece.color == self.game.player_color() => {
+                self.game.get_possible_moves(mv.from, white).unwrap_or_default().into_iter().find(|&possible| possible == mv)
+            }
+            _ => None,
+        }
+    }
+}
+
+
+
+
+// --------------------------
+// ######### TESTS ##########
+// --------------------------
+
+#[cfg(test)]
+mod tests {
+    use crate::{Color, Game, Move, Player, Square};
+
+    use super::{capture_moves, mvv_lva, quiet_moves, MoveOrdering, MovePicker};
+
+    // every move the pieces of the player to move can make, as get_possible_moves gives them
+    fn possible_moves(game: &Game) -> Vec<Move> {
+
+        let white = game.whose_turn() == Player::WhitePlayer;
+
+        Square::all().filter(|&square| matches!(game.get_board().get_piece(square), Some(piece) if (piece.get_color() == Color::White) == white))
+            .flat_map(|square| game.get_possible_moves(square, white).unwrap())
+            .collect()
+    }
+
+    fn picked(mut picker: MovePicker, ordering: &MoveOrdering) -> Vec<Move> {
+
+        let mut moves = Vec::new();
+
+        while let Some(mv) = picker.next(ordering) {
+            moves.push(mv);
+        }
+
+        moves
+    }
+
+    fn uci(moves: &[&str]) -> Vec<Move> {
+        moves.iter().map(|mv| mv.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn captures_and_quiet_moves_are_all_the_moves() {
+
+        for fen in [
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "r2q1rk1/pP1p2pp/Q4n2/bbp1p3/Np6/1B3NBn/pPPP1PPP/R3K2R b KQ - 0 1",
+            "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+        ] {
+
+            let game = Game::from_fen(fen).unwrap();
+
+            let captures = capture_moves(&game);
+            let quiets = quiet_moves(&game);
+            let all = possible_moves(&game);
+
+            assert!(captures.iter().all(|mv| mv.is_capture() || mv.promotion.is_some()), "{}", fen);
+            assert!(quiets.iter().all(|mv| !mv.is_capture() && mv.promotion.is_none()), "{}", fen);
+            assert_eq!(captures.len() + quiets.len(), all.len(), "{}", fen);
+            assert!(all.iter().all(|mv| captures.contains(mv) || quiets.contains(mv)), "{}", fen);
+        }
+    }
+
+    #[test]
+    fn most_valuable_victim_least_valuable_attacker() {
+
+        let game = Game::from_fen("4k3/1P6/8/3q4/2P5/8/3R4/4K3 w - - 0 1").unwrap();
+        let score = |text: &str| mvv_lva(&game.legal_moves().into_iter().find(|mv| mv.to_uci() == text).unwrap());
+
+        assert!(score("c4d5") > score("d2d5")); // a pawn takes the queen before the rook does
+        assert!(score("d2d5") > score("b7b8n")); // a queen is worth more than a knight
+        assert!(score("b7b8q") > score("b7b8n"));
+        assert_eq!(score("e1f1"), 0);
+    }
+
+    #[test]
+    fn killers_history_and_counter_moves() {
+
+        let mut ordering = MoveOrdering::new();
+        let [e2e4, d2d4, c2c4, e7e5] = [uci(&["e2e4"])[0], uci(&["d2d4"])[0], uci(&["c2c4"])[0], uci(&["e7e5"])[0]];
+
+        ordering.add_cutoff(Color::White, e2e4, 3, 2, None);
+        ordering.add_cutoff(Color::White, d2d4, 1, 2, Some(e7e5));
+        ordering.add_cutoff(Color::White, d2d4, 1, 2, None); // the same killer twice does not push out the other one
+
+        assert_eq!(ordering.get_killers(2), [Some(d2d4), Some(e2e4)]);
+        assert_eq!(ordering.get_killers(3), [None, None]);
+
+        ordering.add_cutoff(Color::White, c2c4, 1, 2, None);
+
+        assert_eq!(ordering.get_killers(2), [Some(c2c4), Some(d2d4)]);
+
+        assert_eq!(ordering.get_history(Color::White, e2e4), 9);
+        assert_eq!(ordering.get_history(Color::White, d2d4), 2);
+        assert_eq!(ordering.get_history(Color::Black, e2e4), 0);
+
+        assert_eq!(ordering.get_counter_move(e7e5), Some(d2d4));
+        assert_eq!(ordering.get_counter_move(e2e4), None);
+    }
+
+    #[test]
+    fn picker_goes_through_the_stages() {
+
+        // bxc3 wins a knight, Rxd5 loses the rook to the pawn on c6
+        let game = Game::from_fen("4k3/8/2p5/3p4/8/2n5/1P1R4/4K3 w - - 0 1").unwrap();
+
+        let mut ordering = MoveOrdering::new();
+        let previous = uci(&["c6c5"])[0];
+
+        ordering.add_cutoff(Color::White, uci(&["d2d4"])[0], 1, 0, None);
+        ordering.add_cutoff(Color::White, uci(&["d2h2"])[0], 1, 0, None);
+        ordering.add_cutoff(Color::White, uci(&["d2g2"])[0], 1, 5, Some(previous));
+        ordering.add_cutoff(Color::White, uci(&["e1d1"])[0], 10, 7, None);
+
+        let hash_move = Some(uci(&["e1f1"])[0]);
+        let moves = picked(MovePicker::new(&game, hash_move, &ordering, 0, Some(previous)), &ordering);
+
+        assert_eq!(moves[..6], uci(&["e1f1", "b2c3", "d2h2", "d2d4", "d2g2", "e1d1"]));
+        assert_eq!(moves.last(), Some(&uci(&["d2d5"])[0]));
+
+        // every move comes once
+        let all = possible_moves(&game);
+
+        assert_eq!(moves.len(), all.len());
+        assert!(all.iter().all(|mv| moves.contains(mv)));
+    }
+
+    #[test]
+    fn picker_leaves_out_moves_that_are_not_there() {
+
+        let game = Game::from_fen("4k3/8/2p5/3p4/8/2n5/1P1R4/4K3 w - - 0 1").unwrap();
+        let ordering = MoveOrdering::new();
+
+        // a hash move from another position, with no piece or the wrong piece on its square
+        for hash_move in ["a1a2", "d5d4", "d2e3"] {
+
+            let moves = picked(MovePicker::new(&game, Some(uci(&[hash_move])[0]), &ordering, 0, None), &ordering);
+
+            assert_eq!(moves.first(), Some(&uci(&["b2c3"])[0]));
+            assert_eq!(moves.len(), possible_moves(&game).len());
+        }
+
+        // the captures only picker leaves out the quiet moves and the captures that lose material
+        assert_eq!(picked(MovePicker::captures(&game), &ordering), uci(&["b2c3"]));
+    }
+}
@@ -0,0 +1,141 @@
+//! Counting the positions reachable in a number of moves ("perft"), to check the move generation
+//! against the published counts of well known positions.
+
+use crate::{Game, Move, Player, Square};
+
+
+impl Game {
+
+    /// Counts the different ways to play `depth` half moves from the current position.
+    ///
+    /// Every legal move is followed, whether the game would end by the rules for draws or not, so the
+    /// counts can be compared with other move generators. A depth of 0 counts the current position once.
+    pub fn perft(&self, depth: u32) -> u64 {
+
+        if depth == 0 {
+            return 1;
+        }
+
+        let children = self.perft_children();
+
+        if depth == 1 { //The positions after the last move do not have to be looked at to be counted
+            return children.len() as u64;
+        }
+
+        children.iter().map(|(_, child)| child.perft(depth - 1)).sum()
+    }
+
+    /// Like [`Game::perft`] but the count is split up by the first move, which helps finding the move a
+    /// wrong count comes from. The moves are in the order they are generated in.
+    pub fn perft_divide(&self, depth: u32) -> Vec<(Move, u64)> {
+
+        if depth == 0 {
+            return Vec::new();
+        }
+
+        self.perft_children()
+            .into_iter()
+            .map(|(mv, child)| (mv, child.perft(depth - 1)))
+            .collect()
+    }
+
+    fn perft_children(&self) -> Vec<(Move, Game)> { //Every legal move with the position after it. Only the pieces are moved and the turn passed, perft does not need the rest of the game
+
+        let white = self.player == Player::WhitePlayer;
+
+        Square::all()
+            .filter(|square| matches!(&self.board.squares[square.index()], Some(piece) if piece.color == self.player_color()))
+            .flat_map(|square| self.get_possible_moves(square, white).unwrap_or_default())
+            .filter_map(|mv| {
+
+                let mut child = self.clone();
+
+                child.apply_move(mv);
+
+                if child.is_in_check(self.player_color()) {
+                    return None;
+                }
+
+                child.change_player();
+
+                Some((mv, child))
+            })
+            .collect()
+    }
+}
+
+
+
+
+// --------------------------
+// ######### TESTS ##########
+// --------------------------
+
+#[cfg(test)]
+mod tests {
+    use crate::{Game, Move, Square};
+
+    const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+    const POSITION_3: &str = "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1";
+    const POSITION_4: &str = "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1";
+    const POSITION_5: &str = "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8";
+    const POSITION_6: &str = "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10";
+
+    // checks the counts for the depths 1, 2, ... against the published ones
+    fn assert_perft(fen: &str, counts: &[u64]) {
+
+        let game = Game::from_fen(fen).unwrap();
+
+        for (depth, &count) in counts.iter().enumerate() {
+            assert_eq!(game.perft(depth as u32 + 1), count, "{} at depth {}", fen, depth + 1);
+        }
+    }
+
+    #[test]
+    fn start_position() {
+
+        assert_eq!(Game::new().perft(0), 1);
+        assert_perft(&Game::new().to_fen(), &[20, 400, 8902, 197281]);
+    }
+
+    #[test]
+    fn kiwipete() {
+        assert_perft(KIWIPETE, &[48, 2039, 97862]);
+    }
+
+    #[test]
+    fn other_reference_positions() {
+
+        assert_perft(POSITION_3, &[14, 191, 2812, 43238]);
+        assert_perft(POSITION_4, &[6, 264, 9467]);
+        assert_perft(POSITION_5, &[44, 1486, 62379]);
+        assert_perft(POSITION_6, &[46, 2079, 89890]);
+    }
+
+    #[test]
+    #[ignore = "takes long without optimizations, run with cargo test --release -- --ignored"]
+    fn reference_positions_deeper() {
+
+        let deepest = |fen: &str, depth: u32, count: u64| assert_eq!(Game::from_fen(fen).unwrap().perft(depth), count, "{}", fen);
+
+        deepest(&Game::new().to_fen(), 5, 4865609);
+        deepest(KIWIPETE, 4, 4085603);
+        deepest(POSITION_3, 5, 674624);
+        deepest(POSITION_4, 4, 422333);
+        deepest(POSITION_5, 4, 2103487);
+        deepest(POSITION_6, 4, 3894594);
+    }
+
+    #[test]
+    fn divide_adds_up_to_perft() {
+
+        let game = Game::from_fen(KIWIPETE).unwrap();
+
+        let divide = game.perft_divide(2);
+
+        assert_eq!(divide.len(), 48);
+        assert_eq!(divide.iter().map(|(_, count)| count).sum::<u64>(), 2039);
+        assert!(divide.contains(&(Move::new(Square::E1, Square::G1), 43))); // O-O
+        assert_eq!(game.perft_divide(0), Vec::new());
+    }
+}
@@ -0,0 +1,755 @@
+//! Reading and writing complete games in Portable Game Notation (PGN).
+//!
+//! A game is written with [`Game::to_pgn`]. Files with one or more games are read with
+//! [`PgnReader`], which replays the moves of every game and reports errors per game, so one
+//! broken game does not stop the rest of the file from being read. The moves of variations are
+//! replayed from the position they branch off from and kept as [`Variation`]s.
+
+use std::fmt;
+
+use crate::{FenError, Game, GameOutcome, Move, Player, SanError};
+
+
+/// The tags every PGN game has, in the order they are written.
+const SEVEN_TAG_ROSTER: [&str; 7] = ["Event", "Site", "Date", "Round", "White", "Black", "Result"];
+
+const START_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+
+/// A game read from PGN, with its tags and the moves replayed on a [`Game`].
+#[derive(Clone, Debug)]
+pub struct PgnGame {
+    pub tags: Vec<(String, String)>,
+    pub game: Game,
+    /// The result given at the end of the movetext: "1-0", "0-1", "1/2-1/2" or "*".
+    pub result: String,
+    /// The variations of the main line, in the order they are written.
+    pub variations: Vec<Variation>,
+}
+
+/// A line of moves given instead of a move of the line it branches off from, written in parentheses.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Variation {
+    /// How many moves are played before the first move of the variation, counted from the start of the
+    /// game through the line it branches off from.
+    pub start: usize,
+    pub moves: Vec<Move>,
+    /// The variations inside this one, branching off from its moves.
+    pub variations: Vec<Variation>,
+}
+
+impl PgnGame {
+
+    /// The value of the tag with the given name, if the game has it.
+    pub fn tag(&self, name: &str) -> Option<&str> {
+
+        self.tags.iter().find(|(tag, _)| tag == name).map(|(_, value)| value.as_str())
+    }
+}
+
+
+/// Why a game in a PGN file could not be read. `game` counts the games in the file from 1 and
+/// `line` is the line the problem was found on.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PgnError {
+    pub game: usize,
+    pub line: usize,
+    pub kind: PgnErrorKind,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PgnErrorKind {
+    /// A tag pair is not written as `[Name "value"]`.
+    InvalidTag,
+    /// The FEN tag does not hold a valid position.
+    InvalidFen(FenError),
+    /// A move is not legal in the position it is played in, or not written in SAN.
+    InvalidMove { san: String, reason: SanError },
+    /// A comment is opened with `{` but never closed.
+    UnterminatedComment,
+    /// A variation is closed without being opened, or not closed before the game ends.
+    UnbalancedVariation,
+    /// The movetext does not end with a result.
+    MissingResult,
+    /// Something that is not part of PGN.
+    UnexpectedToken(String),
+}
+
+impl fmt::Display for PgnError {
+
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+
+        write!(f, "game {}, line {}: ", self.game, self.line)?;
+
+        match &self.kind {
+            PgnErrorKind::InvalidTag => write!(f, "invalid tag pair"),
+            PgnErrorKind::InvalidFen(error) => write!(f, "{}", error),
+            PgnErrorKind::InvalidMove { reason, .. } => write!(f, "{}", reason),
+            PgnErrorKind::UnterminatedComment => write!(f, "comment is never closed"),
+            PgnErrorKind::UnbalancedVariation => write!(f, "unbalanced parentheses around a variation"),
+            PgnErrorKind::MissingResult => write!(f, "the movetext does not end with a result"),
+            PgnErrorKind::UnexpectedToken(token) => write!(f, "unexpected \"{}\"", token),
+        }
+    }
+}
+
+impl std::error::Error for PgnError {}
+
+
+impl Game {
+
+    /// Writes the game as PGN.
+    ///
+    /// The seven tag roster is always written, with "?" for the tags not given in `tags`. The result is the
+    /// one given in `tags`, like "0-1" for a game white resigned, or else taken from the game. Other tags are written after the roster in the order given.
+    /// Games that did not start from the normal starting position get the SetUp and FEN tags.
+    pub fn to_pgn(&self, tags: &[(&str, &str)]) -> String {
+
+        let result = tag_value(tags, "Result").unwrap_or(self.result()); //Resignations and losses on time are not seen by the game itself
+
+        let mut pgn = String::new();
+
+        for name in SEVEN_TAG_ROSTER {
+
+            let value = match name {
+                "Result" => result,
+                "Date" => tag_value(tags, name).unwrap_or("????.??.??"),
+                _ => tag_value(tags, name).unwrap_or("?"),
+            };
+
+            push_tag(&mut pgn, name, value);
+        }
+
+        if self.start_fen != START_FEN {
+            push_tag(&mut pgn, "SetUp", "1");
+            push_tag(&mut pgn, "FEN", &self.start_fen);
+        }
+
+        for (name, value) in tags {
+            if !SEVEN_TAG_ROSTER.contains(name) && *name != "SetUp" && *name != "FEN" {
+                push_tag(&mut pgn, name, value);
+            }
+        }
+
+        pgn.push('\n');
+
+        let mut replay = Game::from_fen(&self.start_fen).expect("the start position of a game is always valid");
+
+        let mut words = Vec::new();
+
+        for (index, mv) in self.get_moves().into_iter().enumerate() {
+
+            if replay.player == Player::WhitePlayer {
+                words.push(format!("{}.", replay.fullmove_number));
+            }
+            else if index == 0 { //A game starting with a black move numbers it with three dots
+                words.push(format!("{}...", replay.fullmove_number));
+            }
+
+            words.push(replay.san(mv));
+
+            replay.play_move(mv).expect("the moves of a game are always legal");
+        }
+
+        words.push(result.to_string());
+
+        let mut line_length = 0;
+
+        for word in words { //PGN lines should be at most 80 characters long
+
+            if line_length > 0 && line_length + 1 + word.len() > 80 {
+                pgn.push('\n');
+                line_length = 0;
+            }
+            else if line_length > 0 {
+                pgn.push(' ');
+                line_length += 1;
+            }
+
+            pgn.push_str(&word);
+            line_length += word.len();
+        }
+
+        pgn.push('\n');
+        pgn
+    }
+
+    fn result(&self) -> &'static str { //The result of the game as it is written in PGN
+
+        match self.outcome {
+            Some(GameOutcome::Decisive { winner: Player::WhitePlayer, .. }) => "1-0",
+            Some(GameOutcome::Decisive { winner: Player::BlackPlayer, .. }) => "0-1",
+            Some(GameOutcome::Draw { .. }) => "1/2-1/2",
+            None => "*",
+        }
+    }
+}
+
+fn tag_value<'a>(tags: &[(&str, &'a str)], name: &str) -> Option<&'a str> {
+
+    tags.iter().find(|(tag, _)| *tag == name).map(|(_, value)| *value)
+}
+
+fn push_tag(pgn: &mut String, name: &str, value: &str) {
+
+    let escaped = value.replace('\\', "\\\\").replace('"', "\\\"");
+
+    pgn.push_str(&format!("[{} \"{}\"]\n", name, escaped));
+}
+
+
+/// Reads the games of a PGN file one at a time. Every game gives either the replayed game or the
+/// first error found in it, after which reading goes on with the next game.
+pub struct PgnReader<'a> {
+    lexer: Lexer<'a>,
+    game_number: usize,
+}
+
+impl<'a> PgnReader<'a> {
+
+    pub fn new(text: &'a str) -> PgnReader<'a> {
+
+        PgnReader {
+            lexer: Lexer::new(text),
+            game_number: 0,
+        }
+    }
+}
+
+impl PgnReader<'_> {
+
+    fn error(&self, line: usize, kind: PgnErrorKind) -> Option<Result<PgnGame, PgnError>> {
+
+        Some(Err(PgnError { game: self.game_number, line, kind }))
+    }
+}
+
+impl Iterator for PgnReader<'_> {
+
+    type Item = Result<PgnGame, PgnError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+
+        let mut token = self.lexer.next()?;
+
+        self.game_number += 1;
+
+        let mut tags = Vec::new();
+        let mut game: Option<Result<Movetext, PgnErrorKind>> = None; //Started at the first token of the movetext
+        let mut error: Option<(usize, PgnErrorKind)> = None; //After an error the rest of the game is skipped
+
+        loop {
+
+            let (current, line) = token;
+
+            match current {
+
+                Token::Result(result) => { //The result ends the game
+
+                    if error.is_none() && matches!(&game, Some(Ok(movetext)) if !movetext.lines.is_empty()) {
+                        error = Some((line, PgnErrorKind::UnbalancedVariation));
+                    }
+
+                    if let Some((line, kind)) = error {
+                        return self.error(line, kind);
+                    }
+
+                    return match game.unwrap_or_else(|| start_game(&tags)) {
+                        Ok(Movetext { main, variations, .. }) => Some(Ok(PgnGame { tags, game: main, result, variations })),
+                        Err(kind) => self.error(line, kind),
+                    };
+                }
+
+                Token::Tag(..) if game.is_some() => { //A new tag section means this game ended without a result
+                    self.lexer.push_back(current, line);
+                    let (line, kind) = error.unwrap_or((line, PgnErrorKind::MissingResult));
+                    return self.error(line, kind);
+                }
+
+                _ if error.is_some() => {}
+
+                Token::Tag(name, value) => tags.push((name, value)),
+
+                Token::Error(kind) => error = Some((line, kind)),
+
+                current => {
+
+                    match game.get_or_insert_with(|| start_game(&tags)) {
+                        Ok(movetext) => {
+                            if let Err(kind) = movetext.token(current) {
+                                error = Some((line, kind));
+                            }
+                        }
+                        Err(kind) => error = Some((line, kind.clone())),
+                    }
+                }
+            }
+
+            token = match self.lexer.next() {
+                Some(token) => token,
+                None => {
+                    let (line, kind) = error.unwrap_or((self.lexer.line, PgnErrorKind::MissingResult));
+                    return self.error(line, kind);
+                }
+            };
+        }
+    }
+}
+
+struct Movetext { //The moves of a game read so far
+    main: Game,
+    variations: Vec<Variation>,
+    lines: Vec<(Game, Variation)>, //The variations that are still open with the position at their end, the innermost last
+}
+
+impl Movetext {
+
+    fn token(&mut self, token: Token) -> Result<(), PgnErrorKind> { //Handles one token between the tags and the result
+
+        match token {
+
+            Token::Comment | Token::Nag | Token::MoveNumber => Ok(()),
+
+            Token::VariationStart => { //A variation replaces the last move of the line it is in, so it starts from the position before it
+
+                let (mut game, has_moves) = match self.lines.last() {
+                    Some((game, variation)) => (game.clone(), !variation.moves.is_empty()), //The moves before the variation belong to the line outside it
+                    None => (self.main.clone(), true),
+                };
+
+                if !has_moves || game.undo_move().is_none() {
+                    return Err(PgnErrorKind::UnexpectedToken(String::from("(")));
+                }
+
+                let start = game.history().len();
+
+                self.lines.push((game, Variation { start, moves: Vec::new(), variations: Vec::new() }));
+                Ok(())
+            }
+
+            Token::VariationEnd => {
+
+                let (_, variation) = self.lines.pop().ok_or(PgnErrorKind::UnbalancedVariation)?;
+
+                match self.lines.last_mut() {
+                    Some((_, parent)) => parent.variations.push(variation),
+                    None => self.variations.push(variation),
+                }
+
+                Ok(())
+            }
+
+            Token::San(text) => {
+
+                let (game, variation) = match self.lines.last_mut() {
+                    Some((game, variation)) => (game, Some(variation)),
+                    None => (&mut self.main, None),
+                };
+
+                game.play_san(&text).map_err(|reason| PgnErrorKind::InvalidMove { san: text, reason })?;
+
+                if let (Some(variation), Some(entry)) = (variation, game.history().last()) {
+                    variation.moves.push(entry.mv);
+                }
+
+                Ok(())
+            }
+
+            Token::Tag(..) => Err(PgnErrorKind::UnexpectedToken(String::from("["))),
+
+            Token::Result(result) => Err(PgnErrorKind::UnexpectedToken(result)),
+
+            Token::Error(kind) => Err(kind),
+        }
+    }
+}
+
+fn start_game(tags: &[(String, String)]) -> Result<Movetext, PgnErrorKind> {
+
+    let main = match tags.iter().find(|(name, _)| name == "FEN") {
+        Some((_, fen)) => Game::from_fen(fen).map_err(PgnErrorKind::InvalidFen)?,
+        None => Game::new(),
+    };
+
+    Ok(Movetext { main, variations: Vec::new(), lines: Vec::new() })
+}
+
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Tag(String, String),
+    Comment,
+    Nag,
+    VariationStart,
+    VariationEnd,
+    MoveNumber,
+    San(String),
+    Result(String),
+    Error(PgnErrorKind),
+}
+
+/// Splits PGN text into tokens, keeping track of the line each token is on.
+struct Lexer<'a> {
+    characters: std::iter::Peekable<std::str::Chars<'a>>,
+    line: usize,
+    at_line_start: bool,
+    pushed_back: Option<(Token, usize)>,
+}
+
+impl<'a> Lexer<'a> {
+
+    fn new(text: &'a str) -> Lexer<'a> {
+
+        Lexer {
+            characters: text.chars().peekable(),
+            line: 1,
+            at_line_start: true,
+            pushed_back: None,
+        }
+    }
+
+    fn push_back(&mut self, token: Token, line: usize) {
+        self.pushed_back = Some((token, line));
+    }
+
+    fn bump(&mut self) -> Option<char> {
+
+        let character = self.characters.next()?;
+
+        self.at_line_start = character == '\n';
+
+        if character == '\n' {
+            self.line += 1;
+        }
+
+        Some(character)
+    }
+
+    fn skip_line(&mut self) {
+
+        while let Some(character) = self.bump() {
+            if character == '\n' {
+                break;
+            }
+        }
+    }
+
+    fn tag(&mut self) -> Token {
+
+        let mut name = String::new();
+
+        while let Some(&character) = self.characters.peek() {
+            if character.is_alphanumeric() || character == '_' {
+                name.push(character);
+                self.bump();
+            }
+            else {
+                break;
+            }
+        }
+
+        while self.characters.peek().is_some_and(|character| *character == ' ' || *character == '\t') {
+            self.bump();
+        }
+
+        if name.is_empty() || self.bump() != Some('"') {
+            return self.bad_tag();
+        }
+
+        let mut value = String::new();
+
+        loop {
+            match self.bump() {
+                Some('\\') => match self.bump() {
+                    Some(character) => value.push(character),
+                    None => return Token::Error(PgnErrorKind::InvalidTag),
+                },
+                Some('"') => break,
+                Some('\n') | None => return Token::Error(PgnErrorKind::InvalidTag),
+                Some(character) => value.push(character),
+            }
+        }
+
+        while self.characters.peek().is_some_and(|character| *character == ' ' || *character == '\t') {
+            self.bump();
+        }
+
+        match self.bump() {
+            Some(']') => Token::Tag(name, value),
+            _ => self.bad_tag(),
+        }
+    }
+
+    fn bad_tag(&mut self) -> Token { //The rest of a broken tag pair is skipped
+
+        while let Some(character) = self.bump() {
+            if character == ']' || character == '\n' {
+                break;
+            }
+        }
+
+        Token::Error(PgnErrorKind::InvalidTag)
+    }
+
+    fn symbol(&mut self, first: char) -> Token {
+
+        let mut symbol = String::from(first);
+
+        while let Some(&character) = self.characters.peek() {
+            if character.is_whitespace() || "{}();[]$".contains(character) {
+                break;
+            }
+            symbol.push(character);
+            self.bump();
+        }
+
+        if ["1-0", "0-1", "1/2-1/2", "*"].contains(&symbol.as_str()) {
+            return Token::Result(symbol);
+        }
+
+        //Move numbers like "12." or "12..." may be written right before the move, as in "12.Nf3"
+        let digits = symbol.chars().take_while(|character| character.is_ascii_digit()).count();
+        let rest = symbol[digits..].trim_start_matches('.');
+
+        if digits > 0 && rest.len() < symbol.len() - digits {
+
+            if rest.is_empty() {
+                return Token::MoveNumber;
+            }
+
+            let san = rest.to_string();
+            self.pushed_back = Some((Token::San(san), self.line));
+            return Token::MoveNumber;
+        }
+
+        if symbol.chars().all(|character| character.is_alphanumeric() || "+#=-!?".contains(character)) {
+            Token::San(symbol)
+        }
+        else {
+            Token::Error(PgnErrorKind::UnexpectedToken(symbol))
+        }
+    }
+}
+
+impl Iterator for Lexer<'_> {
+
+    type Item = (Token, usize);
+
+    fn next(&mut self) -> Option<(Token, usize)> {
+
+        if let Some(token) = self.pushed_back.take() {
+            return Some(token);
+        }
+
+        loop {
+
+            if self.at_line_start && self.characters.peek() == Some(&'%') { //Lines starting with % are escaped and ignored
+                self.skip_line();
+                continue;
+            }
+
+            let line = self.line;
+            let character = self.bump()?;
+
+            let token = match character {
+
+                _ if character.is_whitespace() => continue,
+
+                '[' => self.tag(),
+
+                '{' => loop {
+                    match self.bump() {
+                        Some('}') => break Token::Comment,
+                        Some(_) => (),
+                        None => break Token::Error(PgnErrorKind::UnterminatedComment),
+                    }
+                },
+
+                ';' => {
+                    self.skip_line();
+                    Token::Comment
+                }
+
+                '(' => Token::VariationStart,
+                ')' => Token::VariationEnd,
+
+                '$' => {
+                    while self.characters.peek().is_some_and(|character| character.is_ascii_digit()) {
+                        self.bump();
+                    }
+                    Token::Nag
+                }
+
+                _ => self.symbol(character),
+            };
+
+            return Some((token, line));
+        }
+    }
+}
+
+
+
+
+// --------------------------
+// ######### TESTS ##########
+// --------------------------
+
+#[cfg(test)]
+mod tests {
+    use crate::{DrawReason, Game, GameOutcome, Move, SanError, Square};
+
+    use super::{PgnErrorKind, PgnReader};
+
+    fn play_san(game: &mut Game, moves: &[&str]) {
+
+        for text in moves {
+            game.play_san(text).unwrap();
+        }
+    }
+
+    #[test]
+    fn writes_seven_tag_roster_and_movetext() {
+
+        let mut game = Game::new();
+
+        play_san(&mut game, &["f3", "e5", "g4", "Qh4#"]);
+
+        let pgn = game.to_pgn(&[("White", "Alice"), ("Black", "Bob"), ("Opening", "Fool's mate")]);
+
+        assert_eq!(pgn, "[Event \"?\"]\n[Site \"?\"]\n[Date \"????.??.??\"]\n[Round \"?\"]\n[White \"Alice\"]\n[Black \"Bob\"]\n[Result \"0-1\"]\n[Opening \"Fool's mate\"]\n\n1. f3 e5 2. g4 Qh4# 0-1\n");
+    }
+
+    #[test]
+    fn writes_setup_for_games_from_fen() {
+
+        let mut game = Game::from_fen("4k3/8/8/8/8/8/8/R3K3 b Q - 0 30").unwrap();
+
+        play_san(&mut game, &["Kd7", "O-O-O+"]);
+
+        let pgn = game.to_pgn(&[]);
+
+        assert!(pgn.contains("[SetUp \"1\"]\n[FEN \"4k3/8/8/8/8/8/8/R3K3 b Q - 0 30\"]\n"));
+        assert!(pgn.ends_with("\n30... Kd7 31. O-O-O+ *\n"));
+    }
+
+    #[test]
+    fn long_movetext_is_wrapped() {
+
+        let mut game = Game::new();
+
+        for (white_rank, black_rank) in [('3', '6'), ('4', '5')] {
+            for file in "abcdefgh".chars() {
+                play_san(&mut game, &[&format!("{}{}", file, white_rank), &format!("{}{}", file, black_rank)]);
+            }
+        }
+
+        let pgn = game.to_pgn(&[]);
+
+        assert!(pgn.lines().all(|line| line.len() <= 80));
+        assert!(pgn.split("\n\n").nth(1).unwrap().lines().count() > 1);
+    }
+
+    #[test]
+    fn reads_comments_nags_and_variations() {
+
+        let text = "[Event \"Casual\"]\n[White \"A\"]\n[Black \"B\"]\n[Result \"1/2-1/2\"]\n\n\
+            1. e4 {best by test} e5 $1 2. Nf3 (2. f4 exf4 (2... d5) 3. Nf3) 2... Nc6; a comment\n\
+            % an escaped line\n\
+            3.Bb5!? a6 1/2-1/2\n";
+
+        let games: Vec<_> = PgnReader::new(text).collect();
+
+        assert_eq!(games.len(), 1);
+
+        let game = games[0].as_ref().unwrap();
+
+        assert_eq!(game.tag("Event"), Some("Casual"));
+        assert_eq!(game.result, "1/2-1/2");
+        assert_eq!(game.game.get_moves().len(), 6);
+        assert_eq!(game.game.to_fen(), "r1bqkbnr/1ppp1ppp/p1n5/1B2p3/4P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 0 4");
+
+        // 2. f4 replaces 2. Nf3, and 2... d5 inside it replaces 2... exf4
+        let variation = &game.variations[0];
+
+        assert_eq!(game.variations.len(), 1);
+        assert_eq!(variation.start, 2);
+        assert_eq!(variation.moves, [Move::new(Square::F2, Square::F4), Move::new(Square::E5, Square::F4), Move::new(Square::G1, Square::F3)]);
+        assert_eq!(variation.variations.len(), 1);
+        assert_eq!(variation.variations[0].start, 3);
+        assert_eq!(variation.variations[0].moves, [Move::new(Square::D7, Square::D5)]);
+        assert!(variation.variations[0].variations.is_empty());
+
+        // the moves of a variation have to be legal where it starts
+        let error = PgnReader::new("1. e4 e5 2. Nf3 (2. Nf6) Nc6 *").next().unwrap().unwrap_err();
+
+        assert_eq!(error.kind, PgnErrorKind::InvalidMove { san: String::from("Nf6"), reason: SanError::Illegal(String::from("Nf6")) });
+
+        // a variation has to come after a move of the line it is in
+        for text in ["1. e4 ( ( d4 ) ) *", "(1. d4) 1. e4 *", "1. e4 e5 (1... c5 ( (1... e6) ) ) *"] {
+
+            let error = PgnReader::new(text).next().unwrap().unwrap_err();
+
+            assert_eq!(error.kind, PgnErrorKind::UnexpectedToken(String::from("(")), "{}", text);
+        }
+
+        // 2... exf4 is legal in the main line, but the nested variation starts after 2. Nf3
+        let error = PgnReader::new("1. e4 e5 2. f4 (2. Nf3 Nc6 (2... exf4)) exf4 *").next().unwrap().unwrap_err();
+
+        assert_eq!(error.kind, PgnErrorKind::InvalidMove { san: String::from("exf4"), reason: SanError::Illegal(String::from("exf4")) });
+    }
+
+    #[test]
+    fn reports_errors_per_game_and_goes_on() {
+
+        let text = "[Event \"first\"]\n\n1. e4 e5 2. Ke3 Nc6 1-0\n\n\
+            [Event \"second\"]\n\n1. d4 d5 *\n\n\
+            [Event \"third\"]\n[FEN \"8/8/8/8 w - - 0 1\"]\n\n*\n\n\
+            [Event \"fourth\"]\n\n1. c4 {never closed\n";
+
+        let games: Vec<_> = PgnReader::new(text).collect();
+
+        assert_eq!(games.len(), 4);
+
+        let first = games[0].as_ref().unwrap_err();
+        assert_eq!((first.game, first.line), (1, 3));
+        assert_eq!(first.kind, PgnErrorKind::InvalidMove { san: String::from("Ke3"), reason: SanError::Illegal(String::from("Ke3")) });
+        assert_eq!(first.to_string(), "game 1, line 3: \"Ke3\" is not a legal move");
+
+        let second = games[1].as_ref().unwrap();
+        assert_eq!(second.tag("Event"), Some("second"));
+        assert_eq!(second.game.get_moves().len(), 2);
+
+        assert!(matches!(games[2].as_ref().unwrap_err().kind, PgnErrorKind::InvalidFen(_)));
+        assert_eq!(games[3].as_ref().unwrap_err().kind, PgnErrorKind::UnterminatedComment);
+    }
+
+    #[test]
+    fn written_games_can_be_read_back() {
+
+        let mut game = Game::new();
+
+        play_san(&mut game, &["Nf3", "Nf6", "Ng1", "Ng8", "Nf3", "Nf6", "Ng1", "Ng8"]);
+        game.claim_draw();
+
+        let pgn = game.to_pgn(&[("Event", "Club \"Championship\"")]);
+        let read = PgnReader::new(&pgn).next().unwrap().unwrap();
+
+        assert_eq!(read.tag("Event"), Some("Club \"Championship\""));
+        assert_eq!(read.result, "1/2-1/2");
+        assert_eq!(read.game.get_moves(), game.get_moves());
+        assert_eq!(game.get_outcome(), Some(GameOutcome::Draw { reason: DrawReason::ThreefoldRepetition }));
+
+        // a result the game can not see, like white resigning, is taken from the tags
+        let mut resigned = Game::new();
+
+        play_san(&mut resigned, &["e4", "e5"]);
+
+        let pgn = resigned.to_pgn(&[("Result", "0-1")]);
+        let read = PgnReader::new(&pgn).next().unwrap().unwrap();
+
+        assert!(pgn.contains("[Result \"0-1\"]\n") && pgn.ends_with("1. e4 e5 0-1\n"));
+        assert_eq!(read.result, "0-1");
+        assert_eq!(read.game.get_moves(), resigned.get_moves());
+    }
+}
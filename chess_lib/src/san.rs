@@ -0,0 +1,371 @@
+//! Moves in Standard Algebraic Notation (SAN), like "e4", "Nbd7", "exd6", "O-O-O" or "e8=Q+".
+
+use std::fmt;
+
+use crate::fen::piece_letter;
+use crate::{Color, DecisiveReason, File, Game, GameOutcome, GameState, Move, Piece, PieceType, Rank, Square};
+
+
+/// The reasons a SAN move can be refused by [`Game::parse_san`] or [`Game::move_to_san`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum SanError {
+    /// The text is not written like a move in SAN.
+    Syntax(String),
+    /// No legal move in the position matches the move.
+    Illegal(String),
+    /// More than one legal move matches the move, which ones are given in `candidates`.
+    Ambiguous { san: String, candidates: Vec<Move> },
+    /// The game has already ended so no more moves can be made.
+    GameOver,
+}
+
+impl fmt::Display for SanError {
+
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+
+        match self {
+            SanError::Syntax(san) => write!(f, "\"{}\" is not a move in SAN", san),
+            SanError::Illegal(san) => write!(f, "\"{}\" is not a legal move", san),
+            SanError::Ambiguous { san, candidates } => {
+                let squares: Vec<String> = candidates.iter().map(|mv| mv.from.to_string()).collect();
+                write!(f, "\"{}\" could be played from {}", san, squares.join(" or "))
+            }
+            SanError::GameOver => write!(f, "the game is already over"),
+        }
+    }
+}
+
+impl std::error::Error for SanError {}
+
+
+impl Game {
+
+    /// Writes a legal move of the player to move in SAN.
+    ///
+    /// The piece is told apart from others of the same kind that can reach the same square by its
+    /// file, rank or both, and the move is marked with "+" if it gives check and "#" if it gives checkmate.
+    /// A pawn move to the last rank without a promotion piece uses the piece from [`Game::set_promotion`].
+    pub fn move_to_san(&self, mv: Move) -> Result<String, SanError> {
+
+        if self.state == GameState::GameOver {
+            return Err(SanError::GameOver);
+        }
+
+        let mv = Move { promotion: self.promotion_of(mv), ..mv };
+
+        if !self.legal_moves().contains(&mv) {
+            return Err(SanError::Illegal(format!("{}{}", mv.from, mv.to)));
+        }
+
+        Ok(self.san(mv))
+    }
+
+    /// Finds the legal move of the player to move that a move in SAN stands for.
+    ///
+    /// Castling can be written with the letter O or the digit 0. Check and checkmate marks and
+    /// annotations like "!?" at the end are allowed but not checked.
+    pub fn parse_san(&self, text: &str) -> Result<Move, SanError> {
+
+        if self.state == GameState::GameOver {
+            return Err(SanError::GameOver);
+        }
+
+        let san = text.trim_end_matches(['+', '#', '!', '?']);
+
+        if san.is_empty() || !san.is_ascii() {
+            return Err(SanError::Syntax(text.to_string()));
+        }
+
+        let moves = self.legal_moves();
+
+        if let Some(king_side) = match san {
+            "O-O" | "0-0" => Some(true),
+            "O-O-O" | "0-0-0" => Some(false),
+            _ => None,
+        } {
+            return moves.into_iter()
+                .find(|mv| {
+                    self.piece_type_at(mv.from) == Some(PieceType::King)
+                        && mv.to.file() == if king_side { File::G } else { File::C }
+                        && mv.from.file() == File::E
+                })
+                .ok_or_else(|| SanError::Illegal(text.to_string()));
+        }
+
+        let (piece_type, mut rest) = match piece_type_from_letter(san.chars().next().unwrap_or(' ')) {
+            Some(piece_type) => (piece_type, &san[1..]),
+            None => (PieceType::Pawn, san),
+        };
+
+        let mut promotion = None;
+
+        if let Some(index) = rest.find('=') {
+
+            promotion = match piece_type_from_letter(rest[index + 1..].chars().next().unwrap_or(' ')) {
+                Some(piece_type) if rest.len() == index + 2 => Some(piece_type),
+                _ => return Err(SanError::Syntax(text.to_string())),
+            };
+
+            rest = &rest[..index];
+        }
+
+        if rest.len() < 2 {
+            return Err(SanError::Syntax(text.to_string()));
+        }
+
+        let to: Square = rest[rest.len() - 2..].parse().map_err(|_| SanError::Syntax(text.to_string()))?;
+
+        let mut from_file = None;
+        let mut from_rank = None;
+
+        let disambiguation = &rest[..rest.len() - 2];
+
+        for (index, character) in disambiguation.char_indices() {
+            match character {
+                'a'..='h' if from_file.is_none() && from_rank.is_none() => from_file = File::from_char(character),
+                '1'..='8' if from_rank.is_none() => from_rank = Rank::from_char(character),
+                'x' if index == disambiguation.len() - 1 => (),
+                _ => return Err(SanError::Syntax(text.to_string())),
+            }
+        }
+
+        let capture = disambiguation.ends_with('x');
+
+        if piece_type == PieceType::Pawn && capture && from_file.is_none() { //A pawn capture always starts with the file the pawn comes from
+            return Err(SanError::Syntax(text.to_string()));
+        }
+
+        let candidates: Vec<Move> = moves.into_iter()
+            .filter(|mv| {
+                mv.to == to
+                    && self.piece_type_at(mv.from) == Some(piece_type)
+                    && (piece_type != PieceType::Pawn || (mv.is_capture() == capture && (capture || mv.from.file() == to.file()))) //Pawns only go straight ahead without an "x"
+                    && from_file.is_none_or(|file| mv.from.file() == file)
+                    && from_rank.is_none_or(|rank| mv.from.rank() == rank)
+                    && mv.promotion == promotion
+            })
+            .collect();
+
+        match candidates.as_slice() {
+            [mv] => Ok(*mv),
+            [] => Err(SanError::Illegal(text.to_string())),
+            _ => Err(SanError::Ambiguous { san: text.to_string(), candidates }),
+        }
+    }
+
+    /// Plays a move written in SAN, see [`Game::parse_san`].
+    pub fn play_san(&mut self, text: &str) -> Result<GameState, SanError> {
+
+        let mv = self.parse_san(text)?;
+
+        self.play_move(mv).map_err(|_| SanError::Illegal(text.to_string()))
+    }
+
+    fn promotion_of(&self, mv: Move) -> Option<PieceType> { //A promotion without a piece becomes the piece chosen with set_promotion
+
+        match self.is_promotion(mv.from, mv.to) {
+            true => Some(mv.promotion.unwrap_or(self.promotion)),
+            false => mv.promotion,
+        }
+    }
+
+    fn piece_type_at(&self, square: Square) -> Option<PieceType> {
+
+        self.board.squares[square.index()].map(|piece| piece.piece_type)
+    }
+
+    /// Writes a move already known to be legal in SAN.
+    pub(crate) fn san(&self, mv: Move) -> String {
+
+        let piece = self.board.squares[mv.from.index()].expect("a legal move starts on a piece");
+
+        let mut text = String::new();
+
+        if piece.piece_type == PieceType::King && mv.from.file().index().abs_diff(mv.to.file().index()) == 2 {
+            text.push_str(if mv.to.file() == File::G { "O-O" } else { "O-O-O" });
+        }
+        else {
+
+            let capture = self.board.squares[mv.to.index()].is_some()
+                || (piece.piece_type == PieceType::Pawn && mv.from.file() != mv.to.file()); //A pawn moving sideways to an empty square captures en passant
+
+            if piece.piece_type == PieceType::Pawn {
+
+                if capture {
+                    text.push(mv.from.file().to_char());
+                }
+            }
+            else {
+
+                text.push(piece_type_letter(piece.piece_type));
+
+                //Other pieces of the same kind that can go to the same square have to be told apart
+                let rivals: Vec<Square> = self.legal_moves().iter()
+                    .filter(|other| other.to == mv.to && other.from != mv.from && self.board.squares[other.from.index()] == Some(piece))
+                    .map(|other| other.from)
+                    .collect();
+
+                if !rivals.is_empty() {
+
+                    if rivals.iter().all(|rival| rival.file() != mv.from.file()) {
+                        text.push(mv.from.file().to_char());
+                    }
+                    else if rivals.iter().all(|rival| rival.rank() != mv.from.rank()) {
+                        text.push(mv.from.rank().to_char());
+                    }
+                    else {
+                        text.push_str(&mv.from.to_string());
+                    }
+                }
+            }
+
+            if capture {
+                text.push('x');
+            }
+
+            text.push_str(&mv.to.to_string());
+
+            if let Some(promotion) = mv.promotion {
+                text.push('=');
+                text.push(piece_type_letter(promotion));
+            }
+        }
+
+        let mut after = self.clone();
+
+        if after.play_move(mv).is_ok() {
+
+            match after.outcome {
+                Some(GameOutcome::Decisive { reason: DecisiveReason::Checkmate, .. }) => text.push('#'),
+                _ if after.is_in_check(after.player_color()) => text.push('+'),
+                _ => (),
+            }
+        }
+
+        text
+    }
+}
+
+fn piece_type_letter(piece_type: PieceType) -> char {
+
+    piece_letter(&Piece::new(Color::White, piece_type))
+}
+
+fn piece_type_from_letter(letter: char) -> Option<PieceType> { //Pieces in SAN are always upper case, so "b" is a file and "B" a bishop
+
+    match letter {
+        'N' => Some(PieceType::Knight),
+        'B' => Some(PieceType::Bishop),
+        'R' => Some(PieceType::Rook),
+        'Q' => Some(PieceType::Queen),
+        'K' => Some(PieceType::King),
+        _ => None,
+    }
+}
+
+
+
+
+// --------------------------
+// ######### TESTS ##########
+// --------------------------
+
+#[cfg(test)]
+mod tests {
+    use crate::{Game, Move, PieceType, Square};
+
+    use super::SanError;
+
+    #[test]
+    fn formats_disambiguation_captures_and_promotions() {
+
+        let game = Game::from_fen("r3k3/1P6/8/R2Pp3/8/1N3N2/8/R3K3 w Q e6 0 1").unwrap();
+
+        assert_eq!(game.move_to_san(Move::new(Square::B3, Square::D2)).unwrap(), "Nbd2");
+        assert_eq!(game.move_to_san(Move::new(Square::A1, Square::A3)).unwrap(), "R1a3");
+        assert_eq!(game.move_to_san(Move::new(Square::A1, Square::D1)).unwrap(), "Rd1");
+        assert_eq!(game.move_to_san(Move::new(Square::D5, Square::E6)).unwrap(), "dxe6");
+        assert_eq!(game.move_to_san(Move::new(Square::D5, Square::D6)).unwrap(), "d6");
+        assert_eq!(game.move_to_san(Move::new(Square::E1, Square::C1)).unwrap(), "O-O-O");
+        assert_eq!(game.move_to_san(Move::new(Square::B7, Square::A8)).unwrap(), "bxa8=Q+");
+        assert_eq!(game.move_to_san(Move::with_promotion(Square::B7, Square::B8, PieceType::Knight)).unwrap(), "b8=N");
+        assert_eq!(game.move_to_san(Move::new(Square::A1, Square::A8)), Err(SanError::Illegal(String::from("a1a8"))));
+    }
+
+    #[test]
+    fn formats_full_square_when_file_and_rank_are_shared() {
+
+        let game = Game::from_fen("1k6/8/8/8/4Q2Q/8/8/K6Q w - - 0 1").unwrap();
+
+        assert_eq!(game.move_to_san(Move::new(Square::H4, Square::E1)).unwrap(), "Qh4e1");
+        assert_eq!(game.move_to_san(Move::new(Square::H1, Square::E1)).unwrap(), "Q1e1");
+        assert_eq!(game.move_to_san(Move::new(Square::E4, Square::E1)).unwrap(), "Qee1");
+        assert_eq!(game.parse_san("Qh4e1"), Ok(Move::new(Square::H4, Square::E1)));
+    }
+
+    #[test]
+    fn formats_check_and_checkmate() {
+
+        let mut game = Game::new();
+
+        for san in ["e4", "e5", "Bc4", "Nc6", "Qh5", "Nf6"] {
+            game.play_san(san).unwrap();
+        }
+
+        assert_eq!(game.move_to_san(Move::new(Square::H5, Square::F7)).unwrap(), "Qxf7#");
+        assert_eq!(game.move_to_san(Move::new(Square::C4, Square::F7)).unwrap(), "Bxf7+");
+        assert_eq!(game.move_to_san(Move::new(Square::E1, Square::G1)), Err(SanError::Illegal(String::from("e1g1"))));
+    }
+
+    #[test]
+    fn parses_against_the_position() {
+
+        let game = Game::from_fen("r3k3/1P6/8/R2Pp3/8/1N3N2/8/R3K3 w Q e6 0 1").unwrap();
+
+        assert_eq!(game.parse_san("Nfd2"), Ok(Move::new(Square::F3, Square::D2)));
+        assert_eq!(game.parse_san("R5a3"), Ok(Move::new(Square::A5, Square::A3)));
+        assert_eq!(game.parse_san("dxe6!?"), Ok(Move::new(Square::D5, Square::E6)));
+        assert_eq!(game.parse_san("0-0-0"), Ok(Move::new(Square::E1, Square::C1)));
+        assert_eq!(game.parse_san("bxa8=R+"), Ok(Move::with_promotion(Square::B7, Square::A8, PieceType::Rook)));
+        assert_eq!(game.parse_san("exd6"), Err(SanError::Illegal(String::from("exd6"))));
+        assert_eq!(game.parse_san("b8"), Err(SanError::Illegal(String::from("b8"))));
+        assert_eq!(game.parse_san("Nd2"), Err(SanError::Ambiguous {
+            san: String::from("Nd2"),
+            candidates: vec![Move::new(Square::B3, Square::D2), Move::new(Square::F3, Square::D2)],
+        }));
+        assert_eq!(game.parse_san("Nd2").unwrap_err().to_string(), "\"Nd2\" could be played from b3 or f3");
+
+        // a pawn capture has to be written with the file and an "x"
+        let game = Game::from_fen("4k3/8/3n4/2P5/8/8/8/4K3 w - - 0 1").unwrap();
+
+        assert_eq!(game.parse_san("cxd6"), Ok(Move::new(Square::C5, Square::D6)));
+        assert_eq!(game.parse_san("d6"), Err(SanError::Illegal(String::from("d6"))));
+        assert_eq!(game.parse_san("cd6"), Err(SanError::Illegal(String::from("cd6"))));
+        assert_eq!(game.parse_san("xd6"), Err(SanError::Syntax(String::from("xd6"))));
+        assert_eq!(game.parse_san("cxc6"), Err(SanError::Illegal(String::from("cxc6"))));
+    }
+
+    #[test]
+    fn rejects_malformed_moves() {
+
+        let game = Game::new();
+
+        for text in ["", "Xe4", "e9", "Nf3x", "e8=K=Q", "Nxf", "O-O-O-O", "é4"] {
+            assert_eq!(game.parse_san(text), Err(SanError::Syntax(text.to_string())), "{}", text);
+        }
+    }
+
+    #[test]
+    fn castling_in_both_notations() {
+
+        let mut game = Game::new();
+
+        for san in ["e4", "e5", "Nf3", "Nc6", "Bc4", "Bc5"] {
+            game.play_san(san).unwrap();
+        }
+
+        assert_eq!(game.parse_san("O-O"), Ok(Move::new(Square::E1, Square::G1)));
+        assert_eq!(game.parse_san("0-0"), Ok(Move::new(Square::E1, Square::G1)));
+        assert_eq!(game.parse_san("O-O-O"), Err(SanError::Illegal(String::from("O-O-O"))));
+    }
+}
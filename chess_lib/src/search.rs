@@ -0,0 +1,740 @@
+//! Finding the best move for the player to move, so a [`Game`] can be played against the computer.
+//!
+//! The search looks at every line of moves up to a depth with negamax and alpha-beta pruning: the score
+//! of a position is the best of the negated scores of the positions after each move, and the lines that
+//! can not change the result are cut off. It searches to depth 1, then 2 and so on, starting each depth
+//! with the best line found so far, until a limit is reached or it is told to stop. The moves of each
+//! position are tried in the order of the [`ordering`] module.
+//!
+//! At the end of each line the captures and promotions are played out by a quiescence search, so a line
+//! does not end with a piece hanging or just after winning a piece that is about to be taken back.
+//! Captures that lose material by [`Game::static_exchange_eval`] are left out of it.
+//!
+//! Scores are in centipawns, a pawn is 100, from the side of the player to move. Being able to checkmate
+//! is worth [`MATE`] minus the number of half moves it takes, so quicker mates score higher.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::eval::{self, EvalWeights};
+use crate::ordering::{self, MoveOrdering, MovePicker};
+use crate::transposition::{Bound, TranspositionTable};
+use crate::{Game, Move, PieceType};
+
+
+/// The score for checkmating right now. Mates further away score a little less.
+pub const MATE: i32 = 30_000;
+
+/// The most half moves the search looks ahead, which is also the deepest depth it goes to.
+pub const MAX_PLY: u32 = 64;
+
+/// The memory the transposition table of a new [`Search`] uses, in megabytes.
+pub const DEFAULT_TABLE_SIZE: usize = 16;
+
+const CHECK_EVERY: u64 = 1024; //How many nodes are searched between looking at the clock and the stop flag
+
+
+/// When the search should stop. Every limit that is set applies, and the search stops at the first one it reaches.
+/// Without any limits it searches to [`MAX_PLY`] or until the stop flag is set.
+///
+/// The limits can be combined with struct update syntax:
+/// `SearchLimits { nodes: Some(100_000), ..SearchLimits::depth(8) }`.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct SearchLimits {
+    /// The deepest depth to search to, in half moves.
+    pub depth: Option<u32>,
+    /// The most positions to look at.
+    pub nodes: Option<u64>,
+    /// The longest time to search for.
+    pub time: Option<Duration>,
+}
+
+impl SearchLimits {
+
+    pub fn depth(depth: u32) -> SearchLimits {
+
+        SearchLimits { depth: Some(depth), ..SearchLimits::default() }
+    }
+
+    pub fn nodes(nodes: u64) -> SearchLimits {
+
+        SearchLimits { nodes: Some(nodes), ..SearchLimits::default() }
+    }
+
+    pub fn time(time: Duration) -> SearchLimits {
+
+        SearchLimits { time: Some(time), ..SearchLimits::default() }
+    }
+}
+
+
+/// What a search found, from the last depth it finished.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SearchResult {
+    /// The move to play, or `None` if the player to move has no legal moves.
+    pub best_move: Option<Move>,
+    /// The score of the position for the player to move, see the [module documentation](self).
+    pub score: i32,
+    /// The deepest depth that was searched completely. It is 0 if the search was stopped during depth 1.
+    pub depth: u32,
+    /// The number of positions looked at, including the ones of an unfinished depth.
+    pub nodes: u64,
+    /// The line of moves both players are expected to play, starting with the best move.
+    pub pv: Vec<Move>,
+    /// How long the search took.
+    pub time: Duration,
+    /// How full the transposition table is in permille, see [`TranspositionTable::hashfull`].
+    pub hashfull: u32,
+}
+
+impl SearchResult {
+
+    /// The number of moves to checkmate if the score is a mate, negative when the player to move is the one getting mated.
+    pub fn mate_in(&self) -> Option<i32> {
+
+        if self.score > MATE - MAX_PLY as i32 {
+            Some((MATE - self.score + 1) / 2)
+        }
+        else if self.score < -MATE + MAX_PLY as i32 {
+            Some(-(MATE + self.score) / 2)
+        }
+        else {
+            None
+        }
+    }
+}
+
+
+/// A search for the best move with its limits. The search can be stopped from another thread with the
+/// flag from [`Search::stop_flag`], and then returns the result of the last depth it finished.
+///
+/// The positions searched are kept in a [`TranspositionTable`] between runs, so running the same search
+/// again after a move has been played can use what was found before.
+#[derive(Debug)]
+pub struct Search {
+    limits: SearchLimits,
+    weights: EvalWeights,
+    table: TranspositionTable,
+    ordering: MoveOrdering,
+    stop: Arc<AtomicBool>,
+    start: Instant,
+    nodes: u64,
+    next_check: u64, //The node count at which the clock and the stop flag are looked at next
+    stopped: bool,
+    keys: Vec<u64>, //The keys of the positions in the game and the current line, to find repetitions
+    line: Vec<Move>, //The moves played from the start of the search to the current position
+    pv: Vec<Move>, //The best line of the last finished depth, which is searched first at the next depth
+}
+
+impl Search {
+
+    pub fn new(limits: SearchLimits) -> Search {
+
+        Search {
+            limits,
+            weights: EvalWeights::default(),
+            table: TranspositionTable::new(DEFAULT_TABLE_SIZE),
+            ordering: MoveOrdering::new(),
+            stop: Arc::new(AtomicBool::new(false)),
+            start: Instant::now(),
+            nodes: 0,
+            next_check: 0,
+            stopped: false,
+            keys: Vec::new(),
+            line: Vec::new(),
+            pv: Vec::new(),
+        }
+    }
+
+    /// A flag that stops the search when it is set to `true`. The search only notices it every so many
+    /// positions, and does not set it back to `false` when it is done.
+    pub fn stop_flag(&self) -> Arc<AtomicBool> {
+
+        Arc::clone(&self.stop)
+    }
+
+    /// Sets the limits for the next runs, so the same search and its transposition table can be used for every move of a game.
+    pub fn set_limits(&mut self, limits: SearchLimits) {
+
+        self.limits = limits;
+    }
+
+    /// Sets the weights the positions are scored with, see [`eval::evaluate_with`].
+    pub fn set_weights(&mut self, weights: EvalWeights) {
+
+        self.weights = weights;
+    }
+
+    /// Replaces the transposition table with an empty one that uses about `megabytes` of memory.
+    pub fn set_table_size(&mut self, megabytes: usize) {
+
+        self.table = TranspositionTable::new(megabytes);
+    }
+
+    /// The transposition table with the positions searched so far.
+    pub fn get_table(&self) -> &TranspositionTable {
+
+        &self.table
+    }
+
+    /// Throws away the positions searched so far, for example when a new game starts.
+    pub fn clear_table(&mut self) {
+
+        self.table.clear();
+    }
+
+    /// Searches for the best move in the position of the game, deeper and deeper until a limit is reached.
+    /// Draws by repetition and the fifty-move rule are scored as 0 in the lines searched.
+    pub fn run(&mut self, game: &Game) -> SearchResult {
+
+        self.start = Instant::now();
+        self.nodes = 0;
+        self.next_check = 0;
+        self.stopped = false;
+        self.pv.clear();
+        self.line.clear();
+        self.table.new_search();
+        self.ordering.clear();
+
+        //The moves before the last capture or pawn move can not be repeated, and the current position is added by the search itself
+        let earlier = game.positions.len().saturating_sub(1);
+        self.keys = game.positions[earlier.saturating_sub(game.halfmove_clock as usize)..earlier].to_vec();
+
+        //The search only needs the pieces, the turn and the clock, so the copies made for each move leave out the history
+        let mut root = game.clone();
+        root.history = Vec::new();
+        root.undone = Vec::new();
+        root.positions = Vec::new();
+        root.start_fen = String::new();
+
+        let max_depth = self.limits.depth.unwrap_or(MAX_PLY).min(MAX_PLY);
+
+        let mut result = SearchResult {
+            best_move: first_legal_move(&root), //Played if the search is stopped before finishing any depth
+            score: eval::evaluate_with(&root, &self.weights),
+            depth: 0,
+            nodes: 0,
+            pv: Vec::new(),
+            time: Duration::ZERO,
+            hashfull: 0,
+        };
+
+        if result.best_move.is_none() { //Checkmate or stalemate, there is nothing to search
+            result.score = if root.is_in_check(root.player_color()) { -MATE } else { 0 };
+        }
+
+        let mut depth = 1;
+
+        while result.best_move.is_some() && depth <= max_depth {
+
+            let mut pv = Vec::new();
+            let score = self.negamax(&root, depth, 0, -MATE - 1, MATE + 1, &mut pv);
+
+            if self.stopped { //The moves that were not searched yet at this depth could be better, so the unfinished depth is thrown away
+                break;
+            }
+
+            result.best_move = pv.first().copied();
+            result.score = score;
+            result.depth = depth;
+            result.pv = pv.clone();
+            self.pv = pv;
+
+            if score.abs() > MATE - depth as i32 { //Every mate within the depth was found, so a deeper search can not find a quicker one
+                break;
+            }
+
+            depth += 1;
+        }
+
+        result.nodes = self.nodes;
+        result.time = self.start.elapsed();
+        result.hashfull = self.table.hashfull();
+
+        result
+    }
+
+    fn negamax(&mut self, game: &Game, depth: u32, ply: u32, mut alpha: i32, beta: i32, pv: &mut Vec<Move>) -> i32 {
+
+        pv.clear();
+
+        if self.should_stop() {
+            return 0; //The score does not matter, the depth is thrown away
+        }
+
+        self.nodes += 1;
+
+        let key = game.get_hash();
+
+        if ply > 0 && self.is_repetition(key, game.halfmove_clock) {
+            return 0; //A repetition within the search counts as a draw straight away, playing for it again would give the same result
+        }
+
+        if ply > 0 && game.halfmove_clock >= 100 {
+
+            //A mate on the move that reaches the fifty-move rule still counts, so it is only a draw if the player can move
+            return match game.is_in_check(game.player_color()) && first_legal_move(game).is_none() {
+                true => -MATE + ply as i32,
+                false => 0,
+            };
+        }
+
+        if depth == 0 || ply >= MAX_PLY {
+            return self.quiescence(game, ply, alpha, beta);
+        }
+
+        let entry = self.table.probe(key);
+
+        if let Some(entry) = entry.filter(|entry| ply > 0 && entry.depth >= depth) {
+
+            let score = entry.score(ply);
+
+            //A score inside the window would be part of the best line, so the position is searched again to find the moves of the line
+            let cutoff = match entry.bound {
+                Bound::Exact => score <= alpha || score >= beta,
+                Bound::Lower => score >= beta,
+                Bound::Upper => score <= alpha,
+            };
+
+            if cutoff {
+                return score;
+            }
+        }
+
+        //The best move found for the position before, or the move from the best line of the last depth, is likely to be the best again
+        let hash_move = entry.and_then(|entry| entry.best_move).or_else(|| self.pv.get(ply as usize).copied());
+
+        let previous = self.line.last().copied();
+        let mut picker = MovePicker::new(game, hash_move, &self.ordering, ply, previous);
+
+        let original_alpha = alpha;
+        let mut best_move = None;
+        let mut legal_moves = 0;
+
+        self.keys.push(key);
+
+        let mut line = Vec::new();
+
+        while let Some(mv) = picker.next(&self.ordering) {
+
+            let Some(child) = play(game, mv) else { continue };
+
+            legal_moves += 1;
+
+            self.line.push(mv);
+
+            let score = -self.negamax(&child, depth - 1, ply + 1, -beta, -alpha, &mut line);
+
+            self.line.pop();
+
+            if self.stopped {
+                break;
+            }
+
+            if score > alpha {
+
+                alpha = score;
+                best_move = Some(mv);
+
+                pv.clear();
+                pv.push(mv);
+                pv.extend_from_slice(&line);
+
+                if score >= beta { //The opponent would not allow this position, they have a better move earlier in the line
+
+                    if !mv.is_capture() && mv.promotion.is_none() {
+                        self.ordering.add_cutoff(game.player_color(), mv, depth, ply, previous);
+                    }
+
+                    break;
+                }
+            }
+        }
+
+        self.keys.pop();
+
+        if legal_moves == 0 && !self.stopped {
+            return match game.is_in_check(game.player_color()) {
+                true => -MATE + ply as i32,
+                false => 0,
+            };
+        }
+
+        if !self.stopped {
+
+            let bound = if alpha >= beta {
+                Bound::Lower
+            }
+            else if alpha > original_alpha {
+                Bound::Exact
+            }
+            else {
+                Bound::Upper
+            };
+
+            self.table.store(key, depth, bound, alpha, best_move, ply);
+        }
+
+        alpha
+    }
+
+    fn quiescence(&mut self, game: &Game, ply: u32, mut alpha: i32, beta: i32) -> i32 { //Searches only the captures and promotions until the position is quiet
+
+        if ply >= MAX_PLY {
+            return eval::evaluate_with(game, &self.weights);
+        }
+
+        let in_check = game.is_in_check(game.player_color());
+
+        if !in_check { //The player can choose not to capture, so the position is worth at least its score as it is. In check every move has to be looked at
+
+            let standing = eval::evaluate_with(game, &self.weights);
+
+            if standing >= beta {
+                return standing;
+            }
+
+            alpha = alpha.max(standing);
+        }
+
+        //In check every move is looked at, otherwise only the captures that do not lose material
+        let mut picker = match in_check {
+            true => MovePicker::new(game, None, &self.ordering, ply, None),
+            false => MovePicker::captures(game),
+        };
+
+        let mut legal_moves = 0;
+
+        while let Some(mv) = picker.next(&self.ordering) {
+
+            if self.should_stop() {
+                break;
+            }
+
+            let Some(child) = play(game, mv) else { continue };
+
+            legal_moves += 1;
+            self.nodes += 1;
+
+            let score = -self.quiescence(&child, ply + 1, -beta, -alpha);
+
+            if score > alpha {
+
+                alpha = score;
+
+                if score >= beta {
+                    break;
+                }
+            }
+        }
+
+        if in_check && legal_moves == 0 && !self.stopped {
+            return -MATE + ply as i32;
+        }
+
+        alpha
+    }
+
+    fn is_repetition(&self, key: u64, halfmove_clock: u32) -> bool {
+
+        self.keys.iter().rev().take(halfmove_clock as usize).any(|&earlier| earlier == key)
+    }
+
+    fn should_stop(&mut self) -> bool {
+
+        if self.stopped {
+            return true;
+        }
+
+        if self.limits.nodes.is_some_and(|nodes| self.nodes >= nodes) {
+            self.stopped = true;
+        }
+
+        if self.nodes >= self.next_check { //Looking at the clock is slow compared to searching a position, so it is only done now and then
+
+            self.next_check = self.nodes + CHECK_EVERY;
+            self.stopped |= self.stop.load(Ordering::Relaxed) || self.limits.time.is_some_and(|time| self.start.elapsed() >= time);
+        }
+
+        self.stopped
+    }
+}
+
+
+impl Game {
+
+    /// Searches for the best move for the player to move, see [`Search`].
+    ///
+    /// This is only a shortcut for a single search: every call makes a new [`Search`] with an empty
+    /// transposition table of [`DEFAULT_TABLE_SIZE`] megabytes. To search more than once, like for every
+    /// move of a game, keep a [`Search`] and call [`Search::run`] with it so the table is kept between the searches.
+    pub fn search(&self, limits: SearchLimits) -> SearchResult {
+
+        Search::new(limits).run(self)
+    }
+}
+
+
+fn play(game: &Game, mv: Move) -> Option<Game> { //The position after the move with the turn passed and the halfmove clock updated, or None if the move leaves the king in check
+
+    let mut child = game.clone();
+
+    child.apply_move(mv);
+
+    if child.is_in_check(game.player_color()) {
+        return None;
+    }
+
+    child.change_player();
+
+    let pawn_move = matches!(mv.piece, Some(piece) if piece.piece_type == PieceType::Pawn);
+
+    child.halfmove_clock = match pawn_move || mv.is_capture() {
+        true => 0,
+        false => game.halfmove_clock + 1,
+    };
+
+    Some(child)
+}
+
+fn first_legal_move(game: &Game) -> Option<Move> {
+
+    ordering::capture_moves(game).into_iter().chain(ordering::quiet_moves(game)).find(|&mv| play(game, mv).is_some())
+}
+
+
+
+
+// --------------------------
+// ######### TESTS ##########
+// --------------------------
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::Ordering;
+    use std::time::{Duration, Instant};
+
+    use crate::{Game, Move, Square};
+
+    use super::{Search, SearchLimits, MATE};
+
+    #[test]
+    fn finds_mate_in_one() {
+
+        let game = Game::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+        let result = game.search(SearchLimits::depth(3));
+
+        assert_eq!(result.best_move, Some(Move::new(Square::A1, Square::A8)));
+        assert_eq!(result.score, MATE - 1);
+        assert_eq!(result.mate_in(), Some(1));
+        assert_eq!(result.depth, 2); // the mate is seen once black having no moves is searched, and can not get any quicker
+    }
+
+    #[test]
+    fn finds_mate_in_two() {
+
+        let game = Game::from_fen("7k/8/8/8/8/8/R7/1R4K1 w - - 0 1").unwrap();
+        let result = game.search(SearchLimits::depth(5));
+
+        assert_eq!(result.mate_in(), Some(2));
+        assert_eq!(result.pv.len(), 3);
+
+        // the line ends in checkmate when played out
+        let mut line = game.clone();
+
+        for &mv in &result.pv {
+            line.play_move(mv).unwrap();
+        }
+
+        assert!(line.checkmate());
+    }
+
+    #[test]
+    fn sees_getting_mated() {
+
+        // whatever black does, Ra8 is mate next
+        let game = Game::from_fen("7k/5ppp/8/8/8/8/8/R5K1 b - - 0 1").unwrap();
+        let result = game.search(SearchLimits::depth(3));
+
+        assert!(result.best_move.is_some());
+        assert!(result.score < 0);
+
+        let mated = Game::from_fen("R5k1/5ppp/8/8/8/8/8/6K1 b - - 0 1").unwrap();
+        let result = mated.search(SearchLimits::depth(3));
+
+        assert_eq!(result.best_move, None);
+        assert_eq!(result.score, -MATE);
+        assert_eq!(result.mate_in(), Some(0));
+
+        let stalemate = Game::from_fen("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1").unwrap();
+
+        assert_eq!(stalemate.search(SearchLimits::depth(3)).score, 0);
+    }
+
+    #[test]
+    fn takes_a_free_queen_but_not_a_defended_one() {
+
+        let free = Game::from_fen("4k3/8/8/3q4/8/8/3R4/4K3 w - - 0 1").unwrap();
+
+        assert_eq!(free.search(SearchLimits::depth(2)).best_move, Some(Move::new(Square::D2, Square::D5)));
+
+        // taking the pawn loses the rook to the pawn on c6
+        let defended = Game::from_fen("4k3/8/2p5/3p4/8/8/3R4/4K3 w - - 0 1").unwrap();
+        let result = defended.search(SearchLimits::depth(2));
+
+        assert_ne!(result.best_move, Some(Move::new(Square::D2, Square::D5)));
+        assert!(result.score > 0);
+    }
+
+    #[test]
+    fn principal_variation_is_playable() {
+
+        let game = Game::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1").unwrap();
+        let result = game.search(SearchLimits::depth(3));
+
+        assert_eq!(result.depth, 3);
+        assert_eq!(result.pv.len(), 3);
+        assert_eq!(result.pv.first(), result.best_move.as_ref());
+
+        let mut line = game.clone();
+
+        for &mv in &result.pv {
+            assert!(line.play_move(mv).is_ok(), "{} is not legal", mv);
+        }
+    }
+
+    #[test]
+    fn node_and_time_limits() {
+
+        let result = Game::new().search(SearchLimits::nodes(2_000));
+
+        assert!(result.nodes <= 2_000);
+        assert!(result.best_move.is_some());
+        assert!(result.depth >= 1);
+
+        let start = Instant::now();
+        let result = Game::new().search(SearchLimits::time(Duration::from_millis(100)));
+
+        assert!(start.elapsed() < Duration::from_secs(2));
+        assert!(result.best_move.is_some());
+    }
+
+    #[test]
+    fn short_time_limit_returns_promptly() {
+
+        // a position with many captures, so most of the nodes are in the quiescence search
+        let game = Game::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1").unwrap();
+
+        let start = Instant::now();
+        let result = game.search(SearchLimits::time(Duration::from_millis(20)));
+
+        assert!(start.elapsed() < Duration::from_millis(500), "took {:?}", start.elapsed());
+        assert!(result.best_move.is_some());
+    }
+
+    #[test]
+    fn stop_flag_cancels_the_search() {
+
+        let mut search = Search::new(SearchLimits::default());
+
+        search.stop_flag().store(true, Ordering::Relaxed);
+
+        let result = search.run(&Game::new());
+
+        // nothing was searched, but there is still a legal move to play
+        assert_eq!(result.depth, 0);
+        assert!(Game::new().legal_moves().contains(&result.best_move.unwrap()));
+    }
+
+    #[test]
+    fn repetition_is_a_draw() {
+
+        // a queen down, black can only get a draw by going back to a position that was already on the board
+        let mut game = Game::from_fen("7k/8/8/8/8/8/Q7/6K1 b - - 0 1").unwrap();
+
+        for mv in ["h8h7", "g1g2", "h7h8", "g2g1"] {
+            game.play_uci(mv).unwrap();
+        }
+
+        let result = game.search(SearchLimits::depth(3));
+
+        assert_eq!(result.best_move, Some(Move::new(Square::H8, Square::H7)));
+        assert_eq!(result.score, 0);
+
+        // without the earlier positions the same move loses the queens worth
+        let fresh = Game::from_fen("7k/8/8/8/8/8/Q7/6K1 b - - 0 1").unwrap();
+
+        assert!(fresh.search(SearchLimits::depth(3)).score < -800);
+    }
+
+    #[test]
+    fn mate_on_the_fiftieth_move_counts() {
+
+        // Ra8 is the hundredth half move without a capture or pawn move, but it is mate
+        let game = Game::from_fen("7k/8/6K1/8/8/8/8/R7 w - - 99 80").unwrap();
+        let result = game.search(SearchLimits::depth(2));
+
+        assert_eq!(result.best_move, Some(Move::new(Square::A1, Square::A8)));
+        assert_eq!(result.score, MATE - 1);
+
+        // a check that is not mate is still a draw
+        let game = Game::from_fen("7k/8/8/6K1/8/8/8/R7 w - - 99 80").unwrap();
+
+        assert_eq!(game.search(SearchLimits::depth(2)).score, 0);
+    }
+
+    #[test]
+    fn table_is_used_by_the_next_search() {
+
+        let mut search = Search::new(SearchLimits::depth(4));
+
+        let first = search.run(&Game::new());
+        let second = search.run(&Game::new());
+
+        assert!(first.hashfull > 0);
+        assert!(second.nodes < first.nodes / 2);
+        assert_eq!(second.score, first.score);
+        assert_eq!(second.best_move, first.best_move);
+
+        // the same search goes on with other limits for the next move, keeping its table
+        search.set_limits(SearchLimits::depth(5));
+
+        let deeper = search.run(&Game::new());
+
+        assert_eq!(deeper.depth, 5);
+        assert!(search.get_table().hashfull() > 0);
+
+        search.clear_table();
+
+        assert!(search.get_table().is_empty());
+    }
+
+    #[test]
+    fn table_does_not_change_the_score() {
+
+        let game = Game::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1").unwrap();
+
+        // a table with a single slot keeps almost nothing
+        let mut without_table = Search::new(SearchLimits::depth(3));
+
+        without_table.set_table_size(0);
+
+        let with_table = game.search(SearchLimits::depth(3));
+        let without_table = without_table.run(&game);
+
+        assert_eq!(with_table.score, without_table.score);
+        assert!(with_table.nodes < without_table.nodes);
+    }
+
+    #[test]
+    fn captures_at_the_end_of_a_line_are_played_out() {
+
+        // after Qxd5 at depth 1 the pawn on c6 takes the queen
+        let game = Game::from_fen("4k3/8/2p5/3p4/8/8/3Q4/4K3 w - - 0 1").unwrap();
+        let result = game.search(SearchLimits::depth(1));
+
+        assert_ne!(result.best_move, Some(Move::new(Square::D2, Square::D5)));
+        assert!(result.score > 500);
+    }
+}
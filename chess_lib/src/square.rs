@@ -0,0 +1,311 @@
+//! The squares of the board and the files and ranks they are on.
+//!
+//! A [`Square`] can only be made for one of the 64 squares, so a square taken by the API of
+//! [`Game`](crate::Game) is always on the board. Squares are numbered from a1 = 0 to h8 = 63,
+//! going along the ranks first, so b1 = 1 and a2 = 8.
+
+use std::fmt;
+use std::str::FromStr;
+
+
+/// The reasons a [`Square`], [`File`] or [`Rank`] can not be made.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SquareError {
+    /// The index is not on the board (valid squares are 0 to 63 and valid files and ranks 0 to 7).
+    OutOfRange(u32),
+    /// The text is not the name of a square like "e4".
+    InvalidName(String),
+}
+
+impl fmt::Display for SquareError {
+
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+
+        match self {
+            SquareError::OutOfRange(index) => write!(f, "{} is not on the board", index),
+            SquareError::InvalidName(name) => write!(f, "\"{}\" is not the name of a square", name),
+        }
+    }
+}
+
+impl std::error::Error for SquareError {}
+
+
+/// A column of the board, from the a-file on the left of the white player to the h-file.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum File {
+    A, B, C, D, E, F, G, H,
+}
+
+impl File {
+
+    /// Every file from a to h.
+    pub const ALL: [File; 8] = [File::A, File::B, File::C, File::D, File::E, File::F, File::G, File::H];
+
+    /// The file with the given index, a = 0 to h = 7.
+    pub fn new(index: u32) -> Option<File> {
+
+        File::ALL.get(index as usize).copied()
+    }
+
+    /// The file written with the given letter, 'a' to 'h'.
+    pub fn from_char(letter: char) -> Option<File> {
+
+        match letter {
+            'a'..='h' => File::new(letter as u32 - 'a' as u32),
+            _ => None,
+        }
+    }
+
+    pub fn index(self) -> u32 {
+
+        self as u32
+    }
+
+    pub fn to_char(self) -> char {
+
+        (b'a' + self as u8) as char
+    }
+
+    /// The file `offset` files to the right, or `None` if that is off the board.
+    pub fn offset(self, offset: i32) -> Option<File> {
+
+        File::new(u32::try_from(self as i32 + offset).ok()?)
+    }
+}
+
+impl fmt::Display for File {
+
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+
+        write!(f, "{}", self.to_char())
+    }
+}
+
+
+/// A row of the board, from the first rank where the white pieces start to the eighth.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Rank {
+    First, Second, Third, Fourth, Fifth, Sixth, Seventh, Eighth,
+}
+
+impl Rank {
+
+    /// Every rank from the first to the eighth.
+    pub const ALL: [Rank; 8] = [Rank::First, Rank::Second, Rank::Third, Rank::Fourth, Rank::Fifth, Rank::Sixth, Rank::Seventh, Rank::Eighth];
+
+    /// The rank with the given index, the first rank = 0 to the eighth = 7.
+    pub fn new(index: u32) -> Option<Rank> {
+
+        Rank::ALL.get(index as usize).copied()
+    }
+
+    /// The rank written with the given digit, '1' to '8'.
+    pub fn from_char(digit: char) -> Option<Rank> {
+
+        match digit {
+            '1'..='8' => Rank::new(digit as u32 - '1' as u32),
+            _ => None,
+        }
+    }
+
+    pub fn index(self) -> u32 {
+
+        self as u32
+    }
+
+    pub fn to_char(self) -> char {
+
+        (b'1' + self as u8) as char
+    }
+
+    /// The rank `offset` ranks up the board, or `None` if that is off the board.
+    pub fn offset(self, offset: i32) -> Option<Rank> {
+
+        Rank::new(u32::try_from(self as i32 + offset).ok()?)
+    }
+}
+
+impl fmt::Display for Rank {
+
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+
+        write!(f, "{}", self.to_char())
+    }
+}
+
+
+/// One of the 64 squares of the board.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Square(u8);
+
+impl Square {
+
+    pub const A1: Square = Square(0);  pub const B1: Square = Square(1);  pub const C1: Square = Square(2);  pub const D1: Square = Square(3);
+    pub const E1: Square = Square(4);  pub const F1: Square = Square(5);  pub const G1: Square = Square(6);  pub const H1: Square = Square(7);
+    pub const A2: Square = Square(8);  pub const B2: Square = Square(9);  pub const C2: Square = Square(10); pub const D2: Square = Square(11);
+    pub const E2: Square = Square(12); pub const F2: Square = Square(13); pub const G2: Square = Square(14); pub const H2: Square = Square(15);
+    pub const A3: Square = Square(16); pub const B3: Square = Square(17); pub const C3: Square = Square(18); pub const D3: Square = Square(19);
+    pub const E3: Square = Square(20); pub const F3: Square = Square(21); pub const G3: Square = Square(22); pub const H3: Square = Square(23);
+    pub const A4: Square = Square(24); pub const B4: Square = Square(25); pub const C4: Square = Square(26); pub const D4: Square = Square(27);
+    pub const E4: Square = Square(28); pub const F4: Square = Square(29); pub const G4: Square = Square(30); pub const H4: Square = Square(31);
+    pub const A5: Square = Square(32); pub const B5: Square = Square(33); pub const C5: Square = Square(34); pub const D5: Square = Square(35);
+    pub const E5: Square = Square(36); pub const F5: Square = Square(37); pub const G5: Square = Square(38); pub const H5: Square = Square(39);
+    pub const A6: Square = Square(40); pub const B6: Square = Square(41); pub const C6: Square = Square(42); pub const D6: Square = Square(43);
+    pub const E6: Square = Square(44); pub const F6: Square = Square(45); pub const G6: Square = Square(46); pub const H6: Square = Square(47);
+    pub const A7: Square = Square(48); pub const B7: Square = Square(49); pub const C7: Square = Square(50); pub const D7: Square = Square(51);
+    pub const E7: Square = Square(52); pub const F7: Square = Square(53); pub const G7: Square = Square(54); pub const H7: Square = Square(55);
+    pub const A8: Square = Square(56); pub const B8: Square = Square(57); pub const C8: Square = Square(58); pub const D8: Square = Square(59);
+    pub const E8: Square = Square(60); pub const F8: Square = Square(61); pub const G8: Square = Square(62); pub const H8: Square = Square(63);
+
+    /// The square with the given index, a1 = 0 to h8 = 63.
+    pub fn new(index: u32) -> Option<Square> {
+
+        match index {
+            0..=63 => Some(Square(index as u8)),
+            _ => None,
+        }
+    }
+
+    pub fn from_file_rank(file: File, rank: Rank) -> Square {
+
+        Square(rank as u8 * 8 + file as u8)
+    }
+
+    /// Every square from a1 to h8, rank by rank.
+    pub fn all() -> impl Iterator<Item = Square> {
+
+        (0..64).map(Square)
+    }
+
+    pub fn index(self) -> usize {
+
+        self.0 as usize
+    }
+
+    pub fn file(self) -> File {
+
+        File::ALL[(self.0 % 8) as usize]
+    }
+
+    pub fn rank(self) -> Rank {
+
+        Rank::ALL[(self.0 / 8) as usize]
+    }
+
+    /// The square `files` files to the right and `ranks` ranks up the board, or `None` if that is off the board.
+    pub fn offset(self, files: i32, ranks: i32) -> Option<Square> {
+
+        Some(Square::from_file_rank(self.file().offset(files)?, self.rank().offset(ranks)?))
+    }
+
+    /// Returns `true` for the dark squares, like a1.
+    pub fn is_dark(self) -> bool {
+
+        (self.file().index() + self.rank().index()).is_multiple_of(2)
+    }
+}
+
+impl From<Square> for u32 {
+
+    fn from(square: Square) -> u32 {
+        square.0 as u32
+    }
+}
+
+impl TryFrom<u32> for Square {
+
+    type Error = SquareError;
+
+    fn try_from(index: u32) -> Result<Square, SquareError> {
+
+        Square::new(index).ok_or(SquareError::OutOfRange(index))
+    }
+}
+
+impl fmt::Display for Square {
+
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+
+        write!(f, "{}{}", self.file(), self.rank())
+    }
+}
+
+impl FromStr for Square {
+
+    type Err = SquareError;
+
+    fn from_str(name: &str) -> Result<Square, SquareError> { //"e4" gives e4, anything else than the name of a square is an error
+
+        let mut characters = name.chars();
+
+        let file = characters.next().and_then(File::from_char);
+        let rank = characters.next().and_then(Rank::from_char);
+
+        match (file, rank, characters.next()) {
+            (Some(file), Some(rank), None) => Ok(Square::from_file_rank(file, rank)),
+            _ => Err(SquareError::InvalidName(name.to_string())),
+        }
+    }
+}
+
+
+
+
+// --------------------------
+// ######### TESTS ##########
+// --------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::{File, Rank, Square, SquareError};
+
+    #[test]
+    fn indices_files_and_ranks() {
+
+        let square = Square::new(10).unwrap();
+
+        assert_eq!(square, Square::C2);
+        assert_eq!((square.file(), square.rank()), (File::C, Rank::Second));
+        assert_eq!(Square::from_file_rank(File::H, Rank::Eighth), Square::H8);
+        assert_eq!(u32::from(Square::E4), 28);
+
+        assert_eq!(Square::new(64), None);
+        assert_eq!(Square::try_from(64), Err(SquareError::OutOfRange(64)));
+        assert_eq!(File::new(8), None);
+        assert_eq!(Rank::new(8), None);
+    }
+
+    #[test]
+    fn names() {
+
+        assert_eq!(Square::E4.to_string(), "e4");
+        assert_eq!("h8".parse(), Ok(Square::H8));
+
+        for name in ["", "e", "i1", "a0", "a9", "E4", "e44"] {
+            assert_eq!(name.parse::<Square>(), Err(SquareError::InvalidName(name.to_string())), "{}", name);
+        }
+    }
+
+    #[test]
+    fn offsets_stay_on_the_board() {
+
+        assert_eq!(Square::E4.offset(1, 2), Some(Square::F6));
+        assert_eq!(Square::E4.offset(-4, -3), Some(Square::A1));
+        assert_eq!(Square::H1.offset(1, 0), None);
+        assert_eq!(Square::A8.offset(0, 1), None);
+        assert_eq!(File::B.offset(-2), None);
+        assert_eq!(Rank::Seventh.offset(1), Some(Rank::Eighth));
+    }
+
+    #[test]
+    fn iterates_over_every_square() {
+
+        let squares: Vec<Square> = Square::all().collect();
+
+        assert_eq!(squares.len(), 64);
+        assert_eq!((squares[0], squares[63]), (Square::A1, Square::H8));
+        assert!(Square::A1.is_dark());
+        assert!(!Square::H1.is_dark());
+    }
+}
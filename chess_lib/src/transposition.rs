@@ -0,0 +1,256 @@
+//! A table of the positions the search has already looked at, so the same position reached by other
+//! move orders does not have to be searched again.
+//!
+//! The table has a fixed number of slots, worked out from the memory it is allowed to use. A position goes
+//! into the slot picked by its Zobrist key ([`Game::get_hash`](crate::Game::get_hash)), and the whole key is
+//! kept to tell apart the positions that share a slot. When a slot is taken, the new position replaces the old
+//! one if the old one is from an earlier search or was not searched as deep.
+
+use std::mem;
+
+use crate::search::{MATE, MAX_PLY};
+use crate::Move;
+
+
+/// What the score of an [`Entry`] says about the real score of the position.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Bound {
+    /// The score is the real score.
+    Exact,
+    /// The real score is at least the score. A move was found that was too good for the opponent to allow.
+    Lower,
+    /// The real score is at most the score. None of the moves were good enough.
+    Upper,
+}
+
+/// A position in the table. The score is stored the way [`Entry::score`] gives it back.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Entry {
+    pub key: u64,
+    /// The best move found, if any of the moves was better than the others.
+    pub best_move: Option<Move>,
+    /// How many half moves deep the position was searched.
+    pub depth: u32,
+    pub bound: Bound,
+    score: i32, //Mates are counted from this position, not from the position the search started at
+    generation: u8,
+}
+
+impl Entry {
+
+    /// The score of the position with mates counted from the start of the search, for a position `ply` half moves into it.
+    pub fn score(&self, ply: u32) -> i32 {
+
+        if self.score > MATE - MAX_PLY as i32 {
+            self.score - ply as i32
+        }
+        else if self.score < -MATE + MAX_PLY as i32 {
+            self.score + ply as i32
+        }
+        else {
+            self.score
+        }
+    }
+}
+
+
+/// A fixed size table of searched positions, see the [module documentation](self).
+#[derive(Clone, Debug)]
+pub struct TranspositionTable {
+    slots: Vec<Option<Entry>>,
+    generation: u8, //Goes up with every search, so the positions left over from earlier searches are replaced first
+}
+
+impl TranspositionTable {
+
+    /// A table that uses about `megabytes` of memory, with at least one slot.
+    pub fn new(megabytes: usize) -> TranspositionTable {
+
+        let slots = (megabytes * 1024 * 1024 / mem::size_of::<Option<Entry>>()).max(1);
+
+        TranspositionTable {
+            slots: vec![None; slots],
+            generation: 0,
+        }
+    }
+
+    /// The number of positions the table can hold.
+    pub fn len(&self) -> usize {
+
+        self.slots.len()
+    }
+
+    /// Returns `true` if no positions are stored.
+    pub fn is_empty(&self) -> bool {
+
+        self.slots.iter().all(Option::is_none)
+    }
+
+    /// Throws away every position in the table.
+    pub fn clear(&mut self) {
+
+        self.slots.fill(None);
+        self.generation = 0;
+    }
+
+    /// Marks the positions stored so far as left over from an earlier search, to be replaced first.
+    pub fn new_search(&mut self) {
+
+        self.generation = self.generation.wrapping_add(1);
+    }
+
+    /// The position with the key, if it is in the table.
+    pub fn probe(&self, key: u64) -> Option<Entry> {
+
+        self.slots[self.index(key)].filter(|entry| entry.key == key)
+    }
+
+    /// Stores a searched position, `ply` half moves after the position the search started at.
+    /// A move found before is kept if no best move is given for the same position.
+    pub fn store(&mut self, key: u64, depth: u32, bound: Bound, score: i32, best_move: Option<Move>, ply: u32) {
+
+        let generation = self.generation;
+        let index = self.index(key);
+        let slot = &mut self.slots[index];
+
+        let best_move = match slot {
+            Some(old) if old.key == key => best_move.or(old.best_move),
+            _ => best_move,
+        };
+
+        let replace = match slot {
+            None => true,
+            Some(old) => old.generation != generation || depth >= old.depth || bound == Bound::Exact,
+        };
+
+        if !replace {
+            return;
+        }
+
+        let score = if score > MATE - MAX_PLY as i32 { //Mates are counted from this position so they are right wherever it is found again
+            score + ply as i32
+        }
+        else if score < -MATE + MAX_PLY as i32 {
+            score - ply as i32
+        }
+        else {
+            score
+        };
+
+        *slot = Some(Entry { key, best_move, depth, bound, score, generation });
+    }
+
+    /// How full the table is with positions from the current search, in permille like the "hashfull" of UCI engines.
+    /// Only the first thousand slots are counted, which is close enough for a table of random keys.
+    pub fn hashfull(&self) -> u32 {
+
+        let sample = &self.slots[..self.slots.len().min(1000)];
+
+        let used = sample.iter().filter(|slot| matches!(slot, Some(entry) if entry.generation == self.generation)).count();
+
+        (used * 1000 / sample.len()) as u32
+    }
+
+    fn index(&self, key: u64) -> usize {
+
+        (key % self.slots.len() as u64) as usize
+    }
+}
+
+
+
+
+// --------------------------
+// ######### TESTS ##########
+// --------------------------
+
+#[cfg(test)]
+mod tests {
+    use crate::search::MATE;
+    use crate::{Move, Square};
+
+    use super::{Bound, TranspositionTable};
+
+    #[test]
+    fn stores_and_finds_positions() {
+
+        let mut table = TranspositionTable::new(1);
+        let mv = Move::new(Square::E2, Square::E4);
+
+        assert!(table.len() > 1000);
+        assert!(table.is_empty());
+        assert_eq!(table.probe(12345), None);
+
+        table.store(12345, 4, Bound::Lower, 35, Some(mv), 2);
+
+        let entry = table.probe(12345).unwrap();
+
+        assert_eq!((entry.depth, entry.bound, entry.score(2), entry.best_move), (4, Bound::Lower, 35, Some(mv)));
+
+        // a key that goes to the same slot is another position
+        assert_eq!(table.probe(12345 + table.len() as u64), None);
+
+        table.clear();
+
+        assert!(table.is_empty());
+    }
+
+    #[test]
+    fn mates_are_counted_from_where_they_are_found() {
+
+        let mut table = TranspositionTable::new(1);
+
+        // mate in 3 half moves from a position 5 half moves into the search
+        table.store(1, 3, Bound::Exact, MATE - 8, None, 5);
+        table.store(2, 3, Bound::Exact, -MATE + 8, None, 5);
+
+        // found again 1 half move into the search it is 4 half moves away
+        assert_eq!(table.probe(1).unwrap().score(1), MATE - 4);
+        assert_eq!(table.probe(2).unwrap().score(1), -MATE + 4);
+    }
+
+    #[test]
+    fn deeper_and_newer_positions_are_kept() {
+
+        let mut table = TranspositionTable::new(1);
+        let other = 7 + table.len() as u64; // the same slot as 7
+
+        table.store(7, 6, Bound::Lower, 10, None, 0);
+        table.store(other, 2, Bound::Upper, 20, None, 0);
+
+        assert_eq!(table.probe(7).unwrap().depth, 6);
+        assert_eq!(table.probe(other), None);
+
+        // in the next search the old position goes first
+        table.new_search();
+        table.store(other, 2, Bound::Upper, 20, None, 0);
+
+        assert_eq!(table.probe(7), None);
+        assert_eq!(table.probe(other).unwrap().depth, 2);
+
+        // the move is kept when the position is stored again without one
+        let mv = Move::new(Square::G1, Square::F3);
+
+        table.store(other, 3, Bound::Lower, 50, Some(mv), 0);
+        table.store(other, 4, Bound::Upper, 10, None, 0);
+
+        assert_eq!(table.probe(other).unwrap().best_move, Some(mv));
+    }
+
+    #[test]
+    fn hashfull_counts_the_current_search() {
+
+        let mut table = TranspositionTable::new(1);
+
+        for key in 0..500 {
+            table.store(key, 1, Bound::Exact, 0, None, 0);
+        }
+
+        assert_eq!(table.hashfull(), 500);
+
+        table.new_search();
+
+        assert_eq!(table.hashfull(), 0);
+        assert!(!table.is_empty());
+    }
+}
@@ -0,0 +1,170 @@
+//! Moves in the long algebraic notation of the Universal Chess Interface (UCI), like "e2e4",
+//! "e7e8q" or "e1g1". A move is written as the square it starts from and the square it goes to,
+//! followed by the promotion piece in lower case. Castling is written as the two squares the king moves between.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{Game, GameState, Move, MoveError, PieceType, Square};
+
+
+/// The reasons a UCI move can be refused by [`Move::from_uci`] or [`Game::play_uci`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum UciError {
+    /// The text is not written like a move in UCI notation.
+    Syntax(String),
+    /// The move is not allowed in the position it is played in.
+    Illegal(MoveError),
+}
+
+impl fmt::Display for UciError {
+
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+
+        match self {
+            UciError::Syntax(text) => write!(f, "\"{}\" is not a move in UCI notation", text),
+            UciError::Illegal(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for UciError {}
+
+
+impl Move {
+
+    /// Reads a move in UCI notation like "e2e4" or "e7e8q". Whether the move is legal is not checked.
+    pub fn from_uci(text: &str) -> Result<Move, UciError> {
+
+        let syntax = || UciError::Syntax(text.to_string());
+
+        if !text.is_ascii() || !(4..=5).contains(&text.len()) {
+            return Err(syntax());
+        }
+
+        let from: Square = text[0..2].parse().map_err(|_| syntax())?;
+        let to: Square = text[2..4].parse().map_err(|_| syntax())?;
+
+        let promotion = match &text[4..] {
+            "" => None,
+            "q" => Some(PieceType::Queen),
+            "r" => Some(PieceType::Rook),
+            "b" => Some(PieceType::Bishop),
+            "n" => Some(PieceType::Knight),
+            _ => return Err(syntax()),
+        };
+
+        Ok(Move { promotion, ..Move::new(from, to) })
+    }
+
+    /// Writes the move in UCI notation, the same as formatting it with `{}`.
+    pub fn to_uci(&self) -> String {
+
+        let mut text = format!("{}{}", self.from, self.to);
+
+        match self.promotion {
+            Some(PieceType::Queen) => text.push('q'),
+            Some(PieceType::Rook) => text.push('r'),
+            Some(PieceType::Bishop) => text.push('b'),
+            Some(PieceType::Knight) => text.push('n'),
+            _ => (),
+        }
+
+        text
+    }
+}
+
+impl fmt::Display for Move {
+
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+
+        write!(f, "{}", self.to_uci())
+    }
+}
+
+impl FromStr for Move {
+
+    type Err = UciError;
+
+    fn from_str(text: &str) -> Result<Move, UciError> {
+
+        Move::from_uci(text)
+    }
+}
+
+
+impl Game {
+
+    /// Plays a move in UCI notation for the player whose turn it is.
+    ///
+    /// Unlike [`Game::play_move`] a pawn reaching the last rank must name the piece it is promoted
+    /// to, as UCI always does, or the move is refused with [`MoveError::MissingPromotion`].
+    pub fn play_uci(&mut self, text: &str) -> Result<GameState, UciError> {
+
+        let mv = Move::from_uci(text)?;
+
+        if mv.promotion.is_none() && self.is_promotion(mv.from, mv.to) {
+            self.validate_move(mv.from, mv.to).map_err(UciError::Illegal)?; //A move that is not allowed anyway should say why
+            return Err(UciError::Illegal(MoveError::MissingPromotion));
+        }
+
+        self.play_move(mv).map_err(UciError::Illegal)
+    }
+}
+
+
+
+
+// --------------------------
+// ######### TESTS ##########
+// --------------------------
+
+#[cfg(test)]
+mod tests {
+    use crate::{Game, GameState, Move, MoveError, PieceType, Square};
+
+    use super::UciError;
+
+    #[test]
+    fn parses_and_formats_moves() {
+
+        assert_eq!(Move::from_uci("e2e4"), Ok(Move::new(Square::E2, Square::E4)));
+        assert_eq!(Move::from_uci("e7e8q"), Ok(Move::with_promotion(Square::E7, Square::E8, PieceType::Queen)));
+        assert_eq!("a2a1n".parse::<Move>(), Ok(Move::with_promotion(Square::A2, Square::A1, PieceType::Knight)));
+
+        assert_eq!(Move::new(Square::E1, Square::G1).to_uci(), "e1g1");
+        assert_eq!(Move::with_promotion(Square::B7, Square::A8, PieceType::Rook).to_string(), "b7a8r");
+
+        for text in ["", "e2", "e2e9", "e2e4 ", "e7e8Q", "e7e8k", "0000", "e2e4qq"] {
+            assert_eq!(Move::from_uci(text), Err(UciError::Syntax(text.to_string())), "{}", text);
+        }
+    }
+
+    #[test]
+    fn plays_castling_and_promotion() {
+
+        let mut game = Game::from_fen("4k3/1P6/8/8/8/8/8/4K2R w K - 0 1").unwrap();
+
+        assert_eq!(game.play_uci("e1g1"), Ok(GameState::InProgress));
+        assert_eq!(game.to_fen(), "4k3/1P6/8/8/8/8/8/5RK1 b - - 1 1");
+
+        game.play_uci("e8d7").unwrap();
+
+        assert_eq!(game.play_uci("b7b8"), Err(UciError::Illegal(MoveError::MissingPromotion)));
+        assert_eq!(game.play_uci("b7c8"), Err(UciError::Illegal(MoveError::IllegalDestination(Square::C8))));
+        assert_eq!(game.play_uci("b7b8n"), Ok(GameState::Check));
+        assert_eq!(game.get_moves().last().unwrap().to_uci(), "b7b8n");
+    }
+
+    #[test]
+    fn legal_moves_round_trip() {
+
+        let game = Game::from_fen("r3k2r/pPpp1ppp/8/3Pp3/8/8/PPPP1PPP/R3K2R w KQkq e6 0 1").unwrap();
+
+        for mv in Square::all().flat_map(|from| game.get_legal_moves(from)) {
+            assert_eq!(Move::from_uci(&mv.to_uci()), Ok(mv));
+        }
+
+        assert_eq!(game.get_legal_moves(Square::D5).iter().map(|mv| mv.to_uci()).collect::<Vec<_>>(), ["d5d6", "d5e6"]);
+    }
+}